@@ -0,0 +1,138 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+// Generates discriminators, Borsh argument structs and error codes from the bundled pump.fun IDL
+
+const IDL_PATH: &str = "idl/pump.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", IDL_PATH);
+
+    let idl: Value = serde_json::from_str(&fs::read_to_string(IDL_PATH).expect("Failed to read IDL")).expect("Failed to parse IDL");
+    let mut out = String::new();
+
+    for ix in idl["instructions"].as_array().expect("IDL has no instructions") {
+        let name = snake_case(ix["name"].as_str().unwrap());
+        write_discriminator(&mut out, &format!("{}_DISCRIMINATOR", name.to_uppercase()), &format!("global:{}", name));
+
+        let args = ix["args"].as_array().unwrap();
+
+        if !args.is_empty() {
+            write_struct(&mut out, &format!("{}Args", pascal_case(&name)), args);
+        }
+    }
+
+    for account in idl["accounts"].as_array().expect("IDL has no accounts") {
+        let name = account["name"].as_str().unwrap();
+        write_discriminator(&mut out, &format!("{}_ACCOUNT_DISCRIMINATOR", snake_case(name).to_uppercase()), &format!("account:{}", name));
+    }
+
+    for event in idl["events"].as_array().expect("IDL has no events") {
+        let name = event["name"].as_str().unwrap();
+        write_discriminator(&mut out, &format!("{}_DISCRIMINATOR", snake_case(name).to_uppercase()), &format!("event:{}", name));
+    }
+
+    write_errors(&mut out, idl["errors"].as_array().expect("IDL has no errors"));
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("pump_idl.rs");
+    fs::write(out_path, out).expect("Failed to write generated IDL code");
+}
+
+fn write_discriminator(out: &mut String, const_name: &str, preimage: &str) {
+    let hash = Sha256::digest(preimage.as_bytes());
+    let bytes: Vec<String> = hash[..8].iter().map(|b| format!("0x{:02x}", b)).collect();
+
+    writeln!(out, "pub const {}: [u8; 8] = [{}];", const_name, bytes.join(", ")).unwrap();
+}
+
+fn write_struct(out: &mut String, struct_name: &str, fields: &[Value]) {
+    let types: Vec<&str> = fields.iter().map(|field| rust_type(field["type"].as_str().expect("Unsupported IDL type"))).collect();
+    let derive_copy = if types.contains(&"String") { "" } else { "Copy, " };
+
+    writeln!(out, "\n#[derive(Debug, Clone, {}PartialEq, Eq, BorshDeserialize, BorshSerialize)]", derive_copy).unwrap();
+    writeln!(out, "pub struct {} {{", struct_name).unwrap();
+
+    for (field, ty) in fields.iter().zip(types) {
+        writeln!(out, "    pub {}: {},", snake_case(field["name"].as_str().unwrap()), ty).unwrap();
+    }
+
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn write_errors(out: &mut String, errors: &[Value]) {
+    let errors: Vec<(u64, &str, &str)> = errors.iter()
+        .map(|error| (error["code"].as_u64().unwrap(), error["name"].as_str().unwrap(), error["msg"].as_str().unwrap()))
+        .collect();
+
+    writeln!(out, "\n#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]").unwrap();
+    writeln!(out, "pub enum PumpProgramError {{").unwrap();
+    for (code, name, _) in &errors {
+        writeln!(out, "    {} = {},", name, code).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl PumpProgramError {{").unwrap();
+
+    writeln!(out, "    pub fn from_code(code: u32) -> Option<PumpProgramError> {{").unwrap();
+    writeln!(out, "        match code {{").unwrap();
+    for (code, name, _) in &errors {
+        writeln!(out, "            {} => Some(PumpProgramError::{}),", code, name).unwrap();
+    }
+    writeln!(out, "            _ => None,").unwrap();
+    writeln!(out, "        }}\n    }}\n").unwrap();
+
+    writeln!(out, "    pub fn code(&self) -> u32 {{\n        *self as u32\n    }}\n").unwrap();
+
+    writeln!(out, "    pub fn msg(&self) -> &'static str {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for (_, name, msg) in &errors {
+        writeln!(out, "            PumpProgramError::{} => {:?},", name, msg).unwrap();
+    }
+    writeln!(out, "        }}\n    }}\n}}").unwrap();
+}
+
+fn rust_type(idl_type: &str) -> &'static str {
+    match idl_type {
+        "bool" => "bool",
+        "u8" => "u8",
+        "u16" => "u16",
+        "u32" => "u32",
+        "u64" => "u64",
+        "i64" => "i64",
+        "string" => "String",
+        "publicKey" => "Pubkey",
+        other => panic!("Unsupported IDL type {}", other),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+fn pascal_case(snake: &str) -> String {
+    snake.split('_').map(|part| {
+        let mut chars = part.chars();
+        match chars.next() {
+            Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            None => String::new(),
+        }
+    }).collect()
+}
@@ -10,7 +10,7 @@ use solana_client::nonblocking::rpc_client::RpcClient;
 
 use pumpfun_rs::PumpFunClient;
 
-const DEFAULT_SLIPPAGE: f32 = 0.10;
+const DEFAULT_SLIPPAGE_BPS: u64 = 1_000;
 
 #[tokio::main]
 async fn main() {
@@ -20,7 +20,7 @@ async fn main() {
         println!("Usage: ./buy_token <TOKEN_MINT> <AMOUNT_IN_SOL> [SLIPPAGE]");
         println!("  TOKEN_MINT: The token you want to buy");
         println!("  AMOUNT_IN_SOL: SOL amount as a float, e.g. 1.0 = 1 SOL");
-        println!("  SLIPPAGE: Optional slippage tolerance as a float, default is 0.10 (10%)");
+        println!("  SLIPPAGE: Optional slippage tolerance in basis points, default is 1000 (10%)");
         return;
     }
 
@@ -33,12 +33,12 @@ async fn main() {
     let token: Pubkey = Pubkey::from_str(&args[1]).unwrap();
     let amount_in = args[2].parse::<f32>().expect("Error parsing amount");
     let amount_in = (amount_in * 1_000_000_000.0) as u64;
-    let slippage = if args.len() > 3 { args[3].parse::<f32>().unwrap() } else { DEFAULT_SLIPPAGE };
+    let slippage = if args.len() > 3 { args[3].parse::<u64>().unwrap() } else { DEFAULT_SLIPPAGE_BPS };
 
     let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
     let blockhash = rpc_client.get_latest_blockhash().await.expect("Failed to get blockhash");
 
-    let mut pumpfun = PumpFunClient::new(rpc_client, &wallet);
+    let mut pumpfun = PumpFunClient::new(rpc_client, wallet);
 
     let result = pumpfun.buy(&token, amount_in, slippage, true, 1_000_000, &blockhash).await;
 
@@ -6,7 +6,8 @@ use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::pubkey::Pubkey;
 use solana_sdk::commitment_config::CommitmentConfig;
 
-use pumpfun_rs::curve::{derive_bonding_curve_accounts, get_bonding_curve_state};
+use pumpfun_rs::curve::{derive_bonding_curve, get_bonding_curve_state};
+use pumpfun_rs::global::get_global_state;
 
 #[tokio::main]
 async fn main() {
@@ -23,7 +24,7 @@ async fn main() {
     let rpc_url = env::var("RPC_URL").expect("RPC_URL must be set");
     let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
 
-    let (bonding_curve, _) = derive_bonding_curve_accounts(&token);
+    let bonding_curve = derive_bonding_curve(&token);
     let state = get_bonding_curve_state(&rpc_client, &bonding_curve).await;
     
     if state.is_err() {
@@ -34,6 +35,13 @@ async fn main() {
     let state = state.unwrap();
 
     println!("Curve State: {:#?}", state);
-    println!("Price: {:#?}", state.price());
+    println!("Price: {:}", state.price());
+    println!("Market Cap (lamports): {:}", state.market_cap());
+    println!("Tokens Remaining: {:}", state.tokens_remaining());
+    println!("SOL To Complete (lamports): {:?}", state.sol_to_complete());
+
+    if let Ok(global) = get_global_state(&rpc_client).await {
+        println!("Bonding Progress: {:.2}%", state.bonding_progress(&global) as f32 / 100.0);
+    }
 
 }
\ No newline at end of file
@@ -10,7 +10,7 @@ use solana_client::nonblocking::rpc_client::RpcClient;
 use pumpfun_rs::metadata::get_token_metadata;
 use pumpfun_rs::PumpFunClient;
 
-const DEFAULT_SLIPPAGE: f32 = 0.10;
+const DEFAULT_SLIPPAGE_BPS: u64 = 1_000;
 
 #[tokio::main]
 async fn main() {
@@ -19,7 +19,7 @@ async fn main() {
     if args.len() < 2 {
         println!("Usage: ./sell_token <TOKEN_MINT> [SLIPPAGE]");
         println!("  TOKEN_MINT: The mint of the token you want to buy");
-        println!("  SLIPPAGE: Optional slippage tolerance as a float, default is 0.10 (10%)");
+        println!("  SLIPPAGE: Optional slippage tolerance in basis points, default is 1000 (10%)");
         return;
     }
 
@@ -30,14 +30,14 @@ async fn main() {
     let wallet = Keypair::from_base58_string(&wallet);
 
     let token: Pubkey = Pubkey::from_str(&args[1]).unwrap();
-    let slippage = if args.len() > 3 { args[3].parse::<f32>().unwrap() } else { DEFAULT_SLIPPAGE };
+    let slippage = if args.len() > 2 { args[2].parse::<u64>().unwrap() } else { DEFAULT_SLIPPAGE_BPS };
     
-    if !(0.0..=1.0).contains(&slippage) {
+    if slippage > 10_000 {
         println!("Invalid slippage");
         return;
     }
 
-    let mut pumpfun = PumpFunClient::new(RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed()), &wallet);
+    let mut pumpfun = PumpFunClient::new(RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed()), wallet);
 
     let balance = pumpfun.get_balance(&token).await.expect("Failed to get balance");
     
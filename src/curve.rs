@@ -1,12 +1,15 @@
-use std::error::Error;
+use std::collections::HashMap;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 
-use crate::constants::PUMPFUN_PROGRAM_ID;
+use crate::constants::{BONDING_CURVE_ACCOUNT_DISCRIMINATOR, PUMPFUN_PROGRAM_ID};
+use crate::error::PumpFunError;
+use crate::global::GlobalState;
+use crate::price::Price;
 
-#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
 pub struct CurveState {
     _signature: [u8; 8],                // [0x17, 0xb7, 0xf8, 0x37, 0x60, 0xd8, 0xac, 0x60]
     pub virtual_token_reserves: u64,
@@ -17,38 +20,785 @@ pub struct CurveState {
     pub complete: bool,
 }
 
+// Known bonding curve layouts, newer program versions append fields to the account
+#[derive(Debug, Clone)]
+pub enum BondingCurveAccount {
+    V1(CurveState),
+    V2 { state: CurveState, creator: Pubkey },
+}
+
+impl BondingCurveAccount {
+    const V1_LEN: usize = 49;
+    const V2_LEN: usize = BondingCurveAccount::V1_LEN + 32;
+
+    pub fn from_account_data(account_data: &[u8]) -> Result<BondingCurveAccount, PumpFunError> {
+
+        if account_data.len() < 8 || account_data[..8] != BONDING_CURVE_ACCOUNT_DISCRIMINATOR {
+            return Err(PumpFunError::InvalidAccount("Account is not a PumpFun bonding curve.".to_string()));
+        }
+
+        let state = CurveState::deserialize(&mut &account_data[..])?;
+
+        if account_data.len() >= BondingCurveAccount::V2_LEN {
+            let creator = Pubkey::try_from_slice(&account_data[BondingCurveAccount::V1_LEN..BondingCurveAccount::V2_LEN])?;
+            return Ok(BondingCurveAccount::V2 { state, creator });
+        }
+
+        Ok(BondingCurveAccount::V1(state))
+    }
+
+    pub fn state(&self) -> &CurveState {
+        match self {
+            BondingCurveAccount::V1(state) => state,
+            BondingCurveAccount::V2 { state, .. } => state,
+        }
+    }
+
+    pub fn into_state(self) -> CurveState {
+        match self {
+            BondingCurveAccount::V1(state) => state,
+            BondingCurveAccount::V2 { state, .. } => state,
+        }
+    }
+
+    /// Curves created before the creator upgrade were resized with a zeroed creator, which is reported as `None`.
+    pub fn creator(&self) -> Option<Pubkey> {
+        match self {
+            BondingCurveAccount::V1(_) => None,
+            BondingCurveAccount::V2 { creator, .. } if *creator == Pubkey::default() => None,
+            BondingCurveAccount::V2 { creator, .. } => Some(*creator),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeQuote {
+    pub token_amount: u64,
+    pub gross_sol: u64,                 // SOL moving in or out of the curve
+    pub fee_sol: u64,                   // Protocol fee
+    pub creator_fee_sol: u64,
+    pub net_sol: u64,                   // SOL leaving the wallet on a buy, reaching it on a sell
+    pub completes_curve: bool,          // The buy takes the last real tokens and completes the curve
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuoteReport {
+    pub quote: TradeQuote,
+    pub spot_price_before: Price,
+    pub spot_price_after: Price,
+    pub average_price: Price,           // Net SOL per token, fees included
+    pub price_impact: f64,              // Percentage move of the spot price
+    pub market_cap_after: u64,          // Lamports
+}
+
+/// Fees the program charges on top of buys and out of sells, in basis points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeeRates {
+    pub fee_basis_points: u64,
+    pub creator_fee_basis_points: u64,
+}
+
+impl FeeRates {
+    /// Curves without a creator, see `BondingCurveAccount::creator`, pay no creator fee.
+    pub fn new(global: &GlobalState, has_creator: bool) -> FeeRates {
+        FeeRates {
+            fee_basis_points: global.fee_basis_points,
+            creator_fee_basis_points: if has_creator { global.creator_fee_basis_points } else { 0 },
+        }
+    }
+
+    pub fn total_basis_points(&self) -> u64 {
+        self.fee_basis_points.saturating_add(self.creator_fee_basis_points)
+    }
+
+    // Protocol and creator fees are rounded down separately, like the program does
+    fn split(&self, sol_amount: u64) -> (u64, u64) {
+        (fee_amount(sol_amount, self.fee_basis_points), fee_amount(sol_amount, self.creator_fee_basis_points))
+    }
+}
+
+pub fn fee_amount(sol_amount: u64, fee_basis_points: u64) -> u64 {
+    (sol_amount as u128 * fee_basis_points as u128 / 10_000) as u64
+}
+
+/// SOL limit for a trade quoted at `net_sol`: the most a buy may cost, or the least a sell may return.
+/// `slippage_basis_points` of 100 allows a 1% move, sell tolerances are capped at 100%.
+pub fn sol_limit_with_slippage(net_sol: u64, slippage_basis_points: u64, is_buy: bool) -> u64 {
+
+    if is_buy {
+        let max_sol = net_sol as u128 * (10_000 + slippage_basis_points as u128) / 10_000;
+        u64::try_from(max_sol).unwrap_or(u64::MAX)
+    } else {
+        let slippage_sol = net_sol as u128 * slippage_basis_points.min(10_000) as u128 / 10_000;
+        net_sol.saturating_sub(slippage_sol as u64)
+    }
+}
+
 impl CurveState {
-    pub fn price(&self) -> f32 {
+    /// State of a freshly created curve, before any buys.
+    pub fn from_global(global: &GlobalState) -> CurveState {
+        CurveState {
+            _signature: BONDING_CURVE_ACCOUNT_DISCRIMINATOR,
+            virtual_token_reserves: global.initial_virtual_token_reserves,
+            virtual_sol_reserves: global.initial_virtual_sol_reserves,
+            real_token_reserves: global.initial_real_token_reserves,
+            real_sol_reserves: 0,
+            token_total_supply: global.token_total_supply,
+            complete: false,
+        }
+    }
+
+    pub fn price(&self) -> Price {
         
-        if self.complete || self.virtual_token_reserves == 0 || self.virtual_sol_reserves == 0 {
-            return 0.0;
+        if self.complete {
+            return Price::zero();
         }
         
-        (self.virtual_sol_reserves as f32 / 10f32.powi(9)) / (self.virtual_token_reserves as f32 / 10f32.powi(6))
+        spot_price(self)
+    }
+
+    /// Market cap in lamports, valuing the whole token supply at the spot price.
+    pub fn market_cap(&self) -> u64 {
+
+        if self.virtual_token_reserves == 0 {
+            return 0;
+        }
+
+        (self.token_total_supply as u128 * self.virtual_sol_reserves as u128 / self.virtual_token_reserves as u128) as u64
+    }
+
+    /// Share of the initial real token reserves already bought, in basis points (10_000 = 100%).
+    pub fn bonding_progress(&self, global: &GlobalState) -> u64 {
+
+        if self.complete || global.initial_real_token_reserves == 0 {
+            return 10_000;
+        }
+
+        let sold = global.initial_real_token_reserves.saturating_sub(self.real_token_reserves);
+        (sold as u128 * 10_000 / global.initial_real_token_reserves as u128) as u64
+    }
+
+    pub fn tokens_remaining(&self) -> u64 {
+
+        if self.complete {
+            return 0;
+        }
+
+        self.real_token_reserves
+    }
+
+    /// Lamports, before fees, needed to buy every token left on the curve.
+    pub fn sol_to_complete(&self) -> Option<u64> {
+        self.sol_for_tokens(self.tokens_remaining())
+    }
+
+    /// Tokens received for `sol_in` lamports, rounded the same way as the program.
+    /// Capped at the real token reserves left on the curve.
+    pub fn quote_buy(&self, sol_in: u64) -> u64 {
+
+        if sol_in == 0 || self.virtual_token_reserves == 0 {
+            return 0;
+        }
+
+        let virtual_sol_reserves = self.virtual_sol_reserves as u128;
+        let virtual_token_reserves = self.virtual_token_reserves as u128;
+
+        let product = virtual_sol_reserves * virtual_token_reserves;
+        let new_token_reserves = product / (virtual_sol_reserves + sol_in as u128) + 1;
+
+        let tokens_out = virtual_token_reserves.saturating_sub(new_token_reserves) as u64;
+        tokens_out.min(self.real_token_reserves)
+    }
+
+    /// Lamports received for selling `tokens_in` base units, before fees.
+    pub fn quote_sell(&self, tokens_in: u64) -> u64 {
+
+        if tokens_in == 0 || self.virtual_sol_reserves == 0 {
+            return 0;
+        }
+
+        let virtual_sol_reserves = self.virtual_sol_reserves as u128;
+        let virtual_token_reserves = self.virtual_token_reserves as u128;
+
+        (tokens_in as u128 * virtual_sol_reserves / (virtual_token_reserves + tokens_in as u128)) as u64
+    }
+
+    /// Lamports the curve charges, before fees, to buy exactly `tokens_out` base units.
+    pub fn sol_for_tokens(&self, tokens_out: u64) -> Option<u64> {
+
+        if tokens_out == 0 {
+            return Some(0);
+        }
+
+        if tokens_out >= self.virtual_token_reserves {
+            return None;
+        }
+
+        let virtual_sol_reserves = self.virtual_sol_reserves as u128;
+        let virtual_token_reserves = self.virtual_token_reserves as u128;
+
+        let sol_in = tokens_out as u128 * virtual_sol_reserves / (virtual_token_reserves - tokens_out as u128) + 1;
+        u64::try_from(sol_in).ok()
+    }
+
+    /// Base units that must be sold to receive at least `sol_out` lamports, before fees.
+    pub fn tokens_for_sol(&self, sol_out: u64) -> Option<u64> {
+
+        if sol_out == 0 {
+            return Some(0);
+        }
+
+        if sol_out >= self.virtual_sol_reserves {
+            return None;
+        }
+
+        let virtual_sol_reserves = self.virtual_sol_reserves as u128;
+        let virtual_token_reserves = self.virtual_token_reserves as u128;
+
+        let tokens_in = (sol_out as u128 * virtual_token_reserves).div_ceil(virtual_sol_reserves - sol_out as u128);
+        u64::try_from(tokens_in).ok()
+    }
+
+    /// Splits a `sol_in` budget into the curve input and the fees on top of it.
+    pub fn quote_buy_with_fee(&self, sol_in: u64, fees: FeeRates) -> TradeQuote {
+
+        let budget = (sol_in as u128 * 10_000 / (10_000 + fees.total_basis_points() as u128)) as u64;
+        let token_amount = self.quote_buy(budget);
+
+        // The program charges for the token amount, which is under the budget when the final fill is clamped
+        let gross_sol = self.sol_for_tokens(token_amount).unwrap_or(budget).min(budget);
+        let (fee_sol, creator_fee_sol) = fees.split(gross_sol);
+
+        TradeQuote {
+            token_amount,
+            gross_sol,
+            fee_sol,
+            creator_fee_sol,
+            net_sol: gross_sol + fee_sol + creator_fee_sol,
+            completes_curve: token_amount > 0 && token_amount == self.real_token_reserves,
+        }
+    }
+
+    pub fn quote_sell_with_fee(&self, tokens_in: u64, fees: FeeRates) -> TradeQuote {
+
+        let gross_sol = self.quote_sell(tokens_in);
+        let (fee_sol, creator_fee_sol) = fees.split(gross_sol);
+
+        TradeQuote {
+            token_amount: tokens_in,
+            gross_sol,
+            fee_sol,
+            creator_fee_sol,
+            net_sol: gross_sol.saturating_sub(fee_sol).saturating_sub(creator_fee_sol),
+            completes_curve: false,
+        }
+    }
+
+    /// Total cost, fees included, of receiving exactly `tokens_out` base units.
+    /// Orders larger than the real token reserves are clamped to what is left on the curve.
+    pub fn quote_buy_exact_out(&self, tokens_out: u64, fees: FeeRates) -> Option<TradeQuote> {
+
+        let tokens_out = tokens_out.min(self.real_token_reserves);
+        let gross_sol = self.sol_for_tokens(tokens_out)?;
+        let (fee_sol, creator_fee_sol) = fees.split(gross_sol);
+
+        Some(TradeQuote {
+            token_amount: tokens_out,
+            gross_sol,
+            fee_sol,
+            creator_fee_sol,
+            net_sol: gross_sol.checked_add(fee_sol)?.checked_add(creator_fee_sol)?,
+            completes_curve: tokens_out > 0 && tokens_out == self.real_token_reserves,
+        })
+    }
+
+    /// Buys with a `sol_in` budget and moves the reserves the way the program does.
+    /// Returns `None`, leaving the curve untouched, if the program would reject the trade.
+    pub fn apply_buy(&mut self, sol_in: u64, fees: FeeRates) -> Option<TradeQuote> {
+
+        let quote = self.quote_buy_with_fee(sol_in, fees);
+
+        if self.complete || quote.token_amount == 0 {
+            return None;
+        }
+
+        self.move_reserves(&quote, true)?;
+        Some(quote)
+    }
+
+    pub fn apply_sell(&mut self, tokens_in: u64, fees: FeeRates) -> Option<TradeQuote> {
+
+        let quote = self.quote_sell_with_fee(tokens_in, fees);
+
+        if self.complete || quote.token_amount == 0 || fees.total_basis_points() > 10_000 || quote.gross_sol > self.real_sol_reserves {
+            return None;
+        }
+
+        self.move_reserves(&quote, false)?;
+        Some(quote)
+    }
+
+    // Leaves the curve untouched and returns `None` if a reserve would overflow
+    fn move_reserves(&mut self, quote: &TradeQuote, is_buy: bool) -> Option<()> {
+
+        if is_buy {
+            let virtual_token_reserves = self.virtual_token_reserves.checked_sub(quote.token_amount)?;
+            let virtual_sol_reserves = self.virtual_sol_reserves.checked_add(quote.gross_sol)?;
+            let real_sol_reserves = self.real_sol_reserves.checked_add(quote.gross_sol)?;
+
+            self.virtual_token_reserves = virtual_token_reserves;
+            self.real_token_reserves = self.real_token_reserves.saturating_sub(quote.token_amount);
+            self.virtual_sol_reserves = virtual_sol_reserves;
+            self.real_sol_reserves = real_sol_reserves;
+
+            if self.real_token_reserves == 0 {
+                self.complete = true;
+            }
+        } else {
+            let virtual_token_reserves = self.virtual_token_reserves.checked_add(quote.token_amount)?;
+            let real_token_reserves = self.real_token_reserves.checked_add(quote.token_amount)?;
+            let virtual_sol_reserves = self.virtual_sol_reserves.checked_sub(quote.gross_sol)?;
+
+            self.virtual_token_reserves = virtual_token_reserves;
+            self.real_token_reserves = real_token_reserves;
+            self.virtual_sol_reserves = virtual_sol_reserves;
+            self.real_sol_reserves = self.real_sol_reserves.saturating_sub(quote.gross_sol);
+        }
+
+        Some(())
+    }
+
+    /// Smallest sell whose proceeds, after fees, are at least `sol_out` lamports.
+    pub fn quote_sell_exact_out(&self, sol_out: u64, fees: FeeRates) -> Option<TradeQuote> {
+
+        let total_basis_points = fees.total_basis_points();
+
+        if total_basis_points >= 10_000 {
+            return None;
+        }
+
+        let mut gross_sol = u64::try_from((sol_out as u128 * 10_000).div_ceil(10_000 - total_basis_points as u128)).ok()?;
+
+        // Each fee rounds down on its own, so the estimate can overshoot by a few lamports but never undershoot
+        loop {
+            let (fee_sol, creator_fee_sol) = fees.split(gross_sol);
+
+            if gross_sol - fee_sol - creator_fee_sol >= sol_out {
+                break;
+            }
+
+            gross_sol = gross_sol.checked_add(1)?;
+        }
+
+        // The curve can only pay out the SOL it actually holds
+        if gross_sol > self.real_sol_reserves {
+            return None;
+        }
+
+        let tokens_in = self.tokens_for_sol(gross_sol)?;
+        Some(self.quote_sell_with_fee(tokens_in, fees))
     }
 }
 
-pub fn derive_bonding_curve_accounts(mint: &Pubkey) -> (Pubkey, Pubkey) {
+impl QuoteReport {
+    pub fn buy(state: &CurveState, quote: TradeQuote) -> QuoteReport {
+        // A quote the curve cannot absorb leaves `after` unchanged
+        let mut after = state.clone();
+        let _ = after.move_reserves(&quote, true);
+
+        QuoteReport::new(state, &after, quote)
+    }
+
+    pub fn sell(state: &CurveState, quote: TradeQuote) -> QuoteReport {
+        let mut after = state.clone();
+        let _ = after.move_reserves(&quote, false);
+
+        QuoteReport::new(state, &after, quote)
+    }
+
+    fn new(before: &CurveState, after: &CurveState, quote: TradeQuote) -> QuoteReport {
+        let spot_price_before = spot_price(before);
+        let spot_price_after = spot_price(after);
+
+        let average_price = Price::new(quote.net_sol, quote.token_amount);
+        let price_impact = spot_price_before.change_percent(&spot_price_after);
+
+        QuoteReport {
+            quote,
+            spot_price_before,
+            spot_price_after,
+            average_price,
+            price_impact,
+            market_cap_after: after.market_cap(),
+        }
+    }
+}
+
+// Same as CurveState::price() but still valid for a curve the trade has just completed
+fn spot_price(state: &CurveState) -> Price {
+    Price::new(state.virtual_sol_reserves, state.virtual_token_reserves)
+}
+
+pub fn derive_bonding_curve(mint: &Pubkey) -> Pubkey {
 
     let (bonding_curve, _) = Pubkey::find_program_address(
         &["bonding-curve".as_bytes(), mint.as_ref()],
         &PUMPFUN_PROGRAM_ID
     );
 
-    let associated_bonding_curve = get_associated_token_address(&bonding_curve, mint);
+    bonding_curve
+}
+
+/// Bonding curve and its token account. `token_program` is the owner of the mint, the
+/// associated bonding curve of a Token-2022 mint differs from the SPL Token one.
+pub fn derive_bonding_curve_accounts(mint: &Pubkey, token_program: &Pubkey) -> (Pubkey, Pubkey) {
+
+    let bonding_curve = derive_bonding_curve(mint);
+    let associated_bonding_curve = get_associated_token_address_with_program_id(&bonding_curve, mint, token_program);
 
     (bonding_curve, associated_bonding_curve)
 }
 
-pub async fn get_bonding_curve_state(client: &RpcClient, bonding_curve: &Pubkey) -> Result<CurveState, Box<dyn Error>> {
+pub async fn get_bonding_curve_state(client: &RpcClient, bonding_curve: &Pubkey) -> Result<CurveState, PumpFunError> {
+    let account = get_bonding_curve_account(client, bonding_curve).await?;
+    Ok(account.into_state())
+}
+
+// getMultipleAccounts accepts at most 100 keys per request
+const MULTIPLE_ACCOUNTS_CHUNK_SIZE: usize = 100;
+
+/// Fetches the curves of many mints, returning the state per mint and the oldest context slot.
+/// Mints without a valid bonding curve map to `None`.
+pub async fn get_bonding_curve_states(client: &RpcClient, mints: &[Pubkey]) -> Result<(HashMap<Pubkey, Option<CurveState>>, u64), PumpFunError> {
+
+    let mut states = HashMap::with_capacity(mints.len());
+    let mut slot = u64::MAX;
+
+    for chunk in mints.chunks(MULTIPLE_ACCOUNTS_CHUNK_SIZE) {
+        let bonding_curves: Vec<Pubkey> = chunk.iter().map(derive_bonding_curve).collect();
+
+        let response = client.get_multiple_accounts_with_commitment(&bonding_curves, client.commitment()).await?;
+
+        slot = slot.min(response.context.slot);
+
+        for (mint, account) in chunk.iter().zip(response.value) {
+            let state = account
+                .filter(|account| account.owner == PUMPFUN_PROGRAM_ID)
+                .and_then(|account| BondingCurveAccount::from_account_data(&account.data).ok())
+                .map(BondingCurveAccount::into_state);
 
-    match client.get_account_data(bonding_curve).await {
-        Ok(account_data) => {
-            let curve_state = CurveState::try_from_slice(&account_data)?;
-            Ok(curve_state)
-        },
-        Err(e) => {
-            Err(format!("Error getting account data: {:?}", e).into())
+            states.insert(*mint, state);
         }
     }
+
+    if slot == u64::MAX {
+        slot = 0;
+    }
+
+    Ok((states, slot))
+}
+
+pub async fn get_bonding_curve_account(client: &RpcClient, bonding_curve: &Pubkey) -> Result<BondingCurveAccount, PumpFunError> {
+
+    let account = client.get_account(bonding_curve).await?;
+
+    if account.owner != PUMPFUN_PROGRAM_ID {
+        return Err(PumpFunError::InvalidAccount(format!("Bonding curve {:} is not owned by the PumpFun program.", bonding_curve)));
+    }
+
+    BondingCurveAccount::from_account_data(&account.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reserves of a fresh curve, as set in the Global account
+    const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
+    const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000;
+    const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+
+    fn curve(virtual_token_reserves: u64, virtual_sol_reserves: u64, real_token_reserves: u64, real_sol_reserves: u64) -> CurveState {
+        CurveState {
+            _signature: BONDING_CURVE_ACCOUNT_DISCRIMINATOR,
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            real_token_reserves,
+            real_sol_reserves,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+        }
+    }
+
+    fn fresh_curve() -> CurveState {
+        curve(INITIAL_VIRTUAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_REAL_TOKEN_RESERVES, 0)
+    }
+
+    #[test]
+    fn quote_buy_and_sell() {
+        let state = fresh_curve();
+
+        // 1 SOL buys ~34.6M tokens on a fresh curve
+        assert_eq!(state.quote_buy(1_000_000_000), 34_612_903_225_806);
+        assert_eq!(state.quote_buy(0), 0);
+
+        let mut after = state.clone();
+        after.virtual_token_reserves -= 34_612_903_225_806;
+        after.virtual_sol_reserves += 1_000_000_000;
+
+        // Selling straight back loses the rounding lamport
+        assert_eq!(after.quote_sell(34_612_903_225_806), 999_999_999);
+        assert_eq!(after.quote_sell(0), 0);
+    }
+
+    const FEES: FeeRates = FeeRates { fee_basis_points: 95, creator_fee_basis_points: 5 };
+
+    #[test]
+    fn quote_buy_with_fee_splits_budget() {
+        let quote = fresh_curve().quote_buy_with_fee(1_010_000_000, FEES);
+
+        assert_eq!(quote.token_amount, 34_612_903_225_806);
+        assert_eq!(quote.gross_sol, 1_000_000_000);
+        assert_eq!(quote.fee_sol, 9_500_000);
+        assert_eq!(quote.creator_fee_sol, 500_000);
+        assert_eq!(quote.net_sol, 1_010_000_000);
+        assert!(!quote.completes_curve);
+
+        // Without a creator the whole budget but the protocol fee goes into the curve
+        let fees = FeeRates { creator_fee_basis_points: 0, ..FEES };
+        let quote = fresh_curve().quote_buy_with_fee(1_009_500_000, fees);
+
+        assert_eq!(quote.gross_sol, 1_000_000_000);
+        assert_eq!(quote.creator_fee_sol, 0);
+        assert_eq!(quote.net_sol, 1_009_500_000);
+    }
+
+    #[test]
+    fn slippage_limits() {
+        assert_eq!(sol_limit_with_slippage(1_000_000_000, 100, true), 1_010_000_000);
+        assert_eq!(sol_limit_with_slippage(1_000_000_000, 100, false), 990_000_000);
+        assert_eq!(sol_limit_with_slippage(1_000_000_000, 20_000, false), 0);
+        assert_eq!(sol_limit_with_slippage(u64::MAX, 100, true), u64::MAX);
+    }
+
+    // Curve with only 1M tokens left to sell
+    fn almost_complete_curve() -> CurveState {
+        curve(280_000_000_000_000, 115_000_000_000, 1_000_000_000_000, 84_900_000_000)
+    }
+
+    #[test]
+    fn sol_for_tokens_and_tokens_for_sol() {
+        let state = fresh_curve();
+
+        assert_eq!(state.sol_for_tokens(34_612_903_225_806), Some(1_000_000_000));
+        assert_eq!(state.sol_for_tokens(0), Some(0));
+        assert_eq!(state.sol_for_tokens(INITIAL_VIRTUAL_TOKEN_RESERVES), None);
+
+        for sol_in in [1, 1_000, 1_000_000_000, 85_000_000_000] {
+            let tokens_out = state.quote_buy(sol_in);
+            assert!(state.sol_for_tokens(tokens_out).unwrap() <= sol_in);
+        }
+
+        for sol_out in [1, 1_000, 1_000_000_000, 20_000_000_000] {
+            let tokens_in = state.tokens_for_sol(sol_out).unwrap();
+            assert!(state.quote_sell(tokens_in) >= sol_out);
+            assert!(state.quote_sell(tokens_in - 1) < sol_out);
+        }
+
+        assert_eq!(state.tokens_for_sol(INITIAL_VIRTUAL_SOL_RESERVES), None);
+    }
+
+    #[test]
+    fn quote_exact_amounts() {
+        let state = fresh_curve();
+
+        let quote = state.quote_buy_exact_out(34_612_903_225_806, FEES).unwrap();
+        assert_eq!(quote.gross_sol, 1_000_000_000);
+        assert_eq!(quote.net_sol, 1_010_000_000);
+
+        let state = almost_complete_curve();
+
+        // Clamped to the tokens left on the curve
+        let quote = state.quote_buy_exact_out(2_000_000_000_000, FEES).unwrap();
+        assert_eq!(quote.token_amount, state.real_token_reserves);
+        assert!(quote.completes_curve);
+
+        let quote = state.quote_sell_exact_out(1_000_000_000, FEES).unwrap();
+        assert!(quote.net_sol >= 1_000_000_000);
+        assert!(quote.gross_sol <= state.real_sol_reserves);
+
+        // More than the curve holds
+        assert!(state.quote_sell_exact_out(state.real_sol_reserves, FEES).is_none());
+        assert!(state.quote_sell_exact_out(1, FeeRates { fee_basis_points: 10_000, creator_fee_basis_points: 0 }).is_none());
+    }
+
+    #[test]
+    fn quote_report_of_buy_and_sell() {
+        let state = fresh_curve();
+        let quote = state.quote_buy_with_fee(1_010_000_000, FEES);
+        let report = QuoteReport::buy(&state, quote);
+
+        assert_eq!(report.spot_price_before, Price::new(30_000_000_000, 1_073_000_000_000_000));
+        assert_eq!(report.spot_price_after, Price::new(31_000_000_000, 1_038_387_096_774_194));
+        assert_eq!(report.average_price, Price::new(1_010_000_000, 34_612_903_225_806));
+        assert!(report.spot_price_before < report.average_price);
+        // (31 / 30)^2 - 1
+        assert!((report.price_impact - 6.777_777).abs() < 1e-5);
+        assert_eq!(report.market_cap_after, 29_853_991_922);
+
+        // Selling the tokens back returns the spot price to where it was
+        let after = curve(1_038_387_096_774_194, 31_000_000_000, INITIAL_REAL_TOKEN_RESERVES - 34_612_903_225_806, 1_000_000_000);
+
+        let quote = after.quote_sell_with_fee(report.quote.token_amount, FEES);
+        let report = QuoteReport::sell(&after, quote);
+
+        assert_eq!(report.spot_price_before, Price::new(31_000_000_000, 1_038_387_096_774_194));
+        assert!(report.spot_price_after < report.spot_price_before);
+        assert!(report.average_price < report.spot_price_before);
+        assert!((report.price_impact - 6.347_554).abs() < 1e-5);
+    }
+
+    #[test]
+    fn curve_metrics() {
+        let global = GlobalState::from_account_data(&crate::global::tests::v1_account_data()).unwrap();
+
+        let state = fresh_curve();
+        assert_eq!(state.market_cap(), 27_958_993_476);
+        assert_eq!(state.bonding_progress(&global), 0);
+        assert_eq!(state.tokens_remaining(), INITIAL_REAL_TOKEN_RESERVES);
+        assert_eq!(state.sol_to_complete(), state.sol_for_tokens(INITIAL_REAL_TOKEN_RESERVES));
+
+        let state = almost_complete_curve();
+        assert_eq!(state.market_cap(), 410_714_285_714);
+        assert_eq!(state.bonding_progress(&global), 9_987);
+        assert_eq!(state.tokens_remaining(), 1_000_000_000_000);
+        assert_eq!(state.sol_to_complete(), Some(412_186_380));
+
+        let mut state = almost_complete_curve();
+        state.complete = true;
+        assert_eq!(state.bonding_progress(&global), 10_000);
+        assert_eq!(state.tokens_remaining(), 0);
+        assert_eq!(state.sol_to_complete(), Some(0));
+        assert!(state.price().is_zero());
+    }
+
+    #[test]
+    fn apply_buy_and_sell_move_reserves() {
+        let mut state = fresh_curve();
+
+        let buy = state.apply_buy(1_010_000_000, FEES).unwrap();
+        assert_eq!(state.virtual_token_reserves, INITIAL_VIRTUAL_TOKEN_RESERVES - buy.token_amount);
+        assert_eq!(state.virtual_sol_reserves, INITIAL_VIRTUAL_SOL_RESERVES + buy.gross_sol);
+        assert_eq!(state.real_token_reserves, INITIAL_REAL_TOKEN_RESERVES - buy.token_amount);
+        assert_eq!(state.real_sol_reserves, buy.gross_sol);
+
+        let sell = state.apply_sell(buy.token_amount, FEES).unwrap();
+        assert_eq!(sell.gross_sol, 999_999_999);
+        assert_eq!(sell.net_sol, 999_999_999 - 9_499_999 - 499_999);
+        assert_eq!(state.virtual_token_reserves, INITIAL_VIRTUAL_TOKEN_RESERVES);
+        assert_eq!(state.real_token_reserves, INITIAL_REAL_TOKEN_RESERVES);
+        assert_eq!(state.real_sol_reserves, 1);
+
+        // The curve can't pay out more than it holds
+        let before = state.clone();
+        assert!(state.apply_sell(1_000_000_000_000, FEES).is_none());
+        assert_eq!(state.virtual_sol_reserves, before.virtual_sol_reserves);
+    }
+
+    #[test]
+    fn apply_buy_completes_curve() {
+        let mut state = almost_complete_curve();
+
+        let quote = state.apply_buy(100_000_000_000, FEES).unwrap();
+        assert!(quote.completes_curve);
+        assert_eq!(state.real_token_reserves, 0);
+        assert!(state.complete);
+
+        assert!(state.apply_buy(1_000_000_000, FEES).is_none());
+        assert!(state.apply_sell(1_000_000, FEES).is_none());
+    }
+
+    #[test]
+    fn quote_buy_with_fee_clamps_final_fill() {
+        let state = almost_complete_curve();
+        let quote = state.quote_buy_with_fee(100_000_000_000, FEES);
+
+        assert_eq!(state.quote_buy(100_000_000_000), state.real_token_reserves);
+        assert_eq!(quote.token_amount, state.real_token_reserves);
+        assert_eq!(quote.gross_sol, state.sol_for_tokens(state.real_token_reserves).unwrap());
+        assert!(quote.net_sol < 100_000_000_000);
+        assert!(quote.completes_curve);
+    }
+
+    fn account_data(state: &CurveState, creator: Option<Pubkey>) -> Vec<u8> {
+        let mut data = borsh::to_vec(state).unwrap();
+
+        if let Some(creator) = creator {
+            data.extend_from_slice(creator.as_ref());
+        }
+
+        data
+    }
+
+    #[test]
+    fn decode_v1_account() {
+        let data = account_data(&fresh_curve(), None);
+        assert_eq!(data.len(), BondingCurveAccount::V1_LEN);
+
+        let account = BondingCurveAccount::from_account_data(&data).unwrap();
+
+        assert!(matches!(account, BondingCurveAccount::V1(_)));
+        assert_eq!(account.creator(), None);
+        assert_eq!(account.state().virtual_sol_reserves, INITIAL_VIRTUAL_SOL_RESERVES);
+    }
+
+    #[test]
+    fn decode_v2_account() {
+        let creator = Pubkey::new_unique();
+        let account = BondingCurveAccount::from_account_data(&account_data(&fresh_curve(), Some(creator))).unwrap();
+
+        assert!(matches!(account, BondingCurveAccount::V2 { .. }));
+        assert_eq!(account.creator(), Some(creator));
+        assert_eq!(account.into_state().real_token_reserves, INITIAL_REAL_TOKEN_RESERVES);
+    }
+
+    #[test]
+    fn decode_v2_account_with_zeroed_creator() {
+        let account = BondingCurveAccount::from_account_data(&account_data(&fresh_curve(), Some(Pubkey::default()))).unwrap();
+
+        assert!(matches!(account, BondingCurveAccount::V2 { .. }));
+        assert_eq!(account.creator(), None);
+    }
+
+    #[test]
+    fn decode_account_with_trailing_bytes() {
+        let creator = Pubkey::new_unique();
+
+        let mut data = account_data(&fresh_curve(), Some(creator));
+        data.extend_from_slice(&[0xff; 69]);
+
+        let account = BondingCurveAccount::from_account_data(&data).unwrap();
+
+        assert_eq!(account.creator(), Some(creator));
+        assert_eq!(account.state().virtual_token_reserves, INITIAL_VIRTUAL_TOKEN_RESERVES);
+
+        // A V1 account padded with less than a creator is still V1
+        let mut data = account_data(&fresh_curve(), None);
+        data.extend_from_slice(&[0xff; 8]);
+
+        assert!(matches!(BondingCurveAccount::from_account_data(&data).unwrap(), BondingCurveAccount::V1(_)));
+    }
+
+    #[test]
+    fn reject_other_accounts() {
+        let mut data = account_data(&fresh_curve(), None);
+        data[0] ^= 0xff;
+
+        assert!(matches!(BondingCurveAccount::from_account_data(&data), Err(PumpFunError::InvalidAccount(_))));
+        assert!(matches!(BondingCurveAccount::from_account_data(&data[..4]), Err(PumpFunError::InvalidAccount(_))));
+
+        // Right discriminator but truncated
+        let data = account_data(&fresh_curve(), None);
+        assert!(BondingCurveAccount::from_account_data(&data[..20]).is_err());
+    }
 }
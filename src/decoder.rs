@@ -0,0 +1,292 @@
+use borsh::BorshDeserialize;
+use solana_program::instruction::{CompiledInstruction, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::PUMPFUN_PROGRAM_ID;
+use crate::error::PumpFunError;
+use crate::instructions::{BuyArgs, CreateArgs, SellArgs, SetParamsArgs, BUY_DISCRIMINATOR, COLLECT_CREATOR_FEE_DISCRIMINATOR, CREATE_DISCRIMINATOR, INITIALIZE_DISCRIMINATOR, SELL_DISCRIMINATOR, SET_PARAMS_DISCRIMINATOR, WITHDRAW_DISCRIMINATOR};
+
+#[derive(Debug, Clone)]
+pub enum PumpInstruction {
+    Initialize {
+        global: Pubkey,
+        user: Pubkey,
+    },
+    SetParams {
+        global: Pubkey,
+        user: Pubkey,
+        args: SetParamsArgs,
+    },
+    Create {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        associated_bonding_curve: Pubkey,
+        metadata: Pubkey,
+        user: Pubkey,
+        args: CreateArgs,
+    },
+    Buy {
+        fee_recipient: Pubkey,
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        associated_bonding_curve: Pubkey,
+        associated_user: Pubkey,
+        user: Pubkey,
+        creator_vault: Pubkey,
+        token_program: Pubkey,
+        args: BuyArgs,
+    },
+    Sell {
+        fee_recipient: Pubkey,
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        associated_bonding_curve: Pubkey,
+        associated_user: Pubkey,
+        user: Pubkey,
+        creator_vault: Pubkey,
+        token_program: Pubkey,
+        args: SellArgs,
+    },
+    Withdraw {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        associated_bonding_curve: Pubkey,
+        associated_user: Pubkey,
+        user: Pubkey,
+    },
+    CollectCreatorFee {
+        creator: Pubkey,
+        creator_vault: Pubkey,
+    },
+}
+
+impl PumpInstruction {
+    pub fn decode(ix: &Instruction) -> Result<PumpInstruction, PumpFunError> {
+        let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        PumpInstruction::decode_parts(&ix.program_id, &accounts, &ix.data)
+    }
+
+    /// Decodes an instruction from a transaction message. `account_keys` must include the
+    /// addresses loaded from lookup tables when the message is a v0 message.
+    pub fn decode_compiled(ix: &CompiledInstruction, account_keys: &[Pubkey]) -> Result<PumpInstruction, PumpFunError> {
+
+        let program_id = match account_keys.get(ix.program_id_index as usize) {
+            Some(program_id) => program_id,
+            None => return Err(PumpFunError::InvalidInstruction("Program id index out of range.".to_string())),
+        };
+
+        let mut accounts: Vec<Pubkey> = Vec::with_capacity(ix.accounts.len());
+
+        for index in &ix.accounts {
+            match account_keys.get(*index as usize) {
+                Some(account) => accounts.push(*account),
+                None => return Err(PumpFunError::InvalidInstruction("Account index out of range.".to_string())),
+            }
+        }
+
+        PumpInstruction::decode_parts(program_id, &accounts, &ix.data)
+    }
+
+    fn decode_parts(program_id: &Pubkey, accounts: &[Pubkey], data: &[u8]) -> Result<PumpInstruction, PumpFunError> {
+
+        if *program_id != PUMPFUN_PROGRAM_ID {
+            return Err(PumpFunError::InvalidInstruction("Not a PumpFun instruction.".to_string()));
+        }
+
+        if data.len() < 8 {
+            return Err(PumpFunError::InvalidInstruction("Instruction data is too short.".to_string()));
+        }
+
+        let (discriminator, mut args) = data.split_at(8);
+
+        let instruction = match discriminator {
+            d if d == INITIALIZE_DISCRIMINATOR => {
+                let accounts = expect_accounts(accounts, 2)?;
+
+                PumpInstruction::Initialize {
+                    global: accounts[0],
+                    user: accounts[1],
+                }
+            },
+            d if d == SET_PARAMS_DISCRIMINATOR => {
+                let accounts = expect_accounts(accounts, 2)?;
+
+                PumpInstruction::SetParams {
+                    global: accounts[0],
+                    user: accounts[1],
+                    args: SetParamsArgs::deserialize(&mut args)?,
+                }
+            },
+            d if d == CREATE_DISCRIMINATOR => {
+                let accounts = expect_accounts(accounts, 8)?;
+
+                PumpInstruction::Create {
+                    mint: accounts[0],
+                    bonding_curve: accounts[2],
+                    associated_bonding_curve: accounts[3],
+                    metadata: accounts[6],
+                    user: accounts[7],
+                    args: CreateArgs::deserialize(&mut args)?,
+                }
+            },
+            d if d == BUY_DISCRIMINATOR => {
+                let accounts = expect_accounts(accounts, 10)?;
+
+                PumpInstruction::Buy {
+                    fee_recipient: accounts[1],
+                    mint: accounts[2],
+                    bonding_curve: accounts[3],
+                    associated_bonding_curve: accounts[4],
+                    associated_user: accounts[5],
+                    user: accounts[6],
+                    creator_vault: accounts[9],
+                    token_program: accounts[8],
+                    args: BuyArgs::deserialize(&mut args)?,
+                }
+            },
+            d if d == SELL_DISCRIMINATOR => {
+                let accounts = expect_accounts(accounts, 10)?;
+
+                PumpInstruction::Sell {
+                    fee_recipient: accounts[1],
+                    mint: accounts[2],
+                    bonding_curve: accounts[3],
+                    associated_bonding_curve: accounts[4],
+                    associated_user: accounts[5],
+                    user: accounts[6],
+                    creator_vault: accounts[8],
+                    token_program: accounts[9],
+                    args: SellArgs::deserialize(&mut args)?,
+                }
+            },
+            d if d == WITHDRAW_DISCRIMINATOR => {
+                let accounts = expect_accounts(accounts, 6)?;
+
+                PumpInstruction::Withdraw {
+                    mint: accounts[1],
+                    bonding_curve: accounts[2],
+                    associated_bonding_curve: accounts[3],
+                    associated_user: accounts[4],
+                    user: accounts[5],
+                }
+            },
+            d if d == COLLECT_CREATOR_FEE_DISCRIMINATOR => {
+                let accounts = expect_accounts(accounts, 2)?;
+
+                PumpInstruction::CollectCreatorFee {
+                    creator: accounts[0],
+                    creator_vault: accounts[1],
+                }
+            },
+            _ => return Err(PumpFunError::InvalidInstruction(format!("Unknown PumpFun instruction discriminator {:02x?}", discriminator))),
+        };
+
+        Ok(instruction)
+    }
+}
+
+fn expect_accounts(accounts: &[Pubkey], count: usize) -> Result<&[Pubkey], PumpFunError> {
+
+    if accounts.len() < count {
+        return Err(PumpFunError::InvalidInstruction(format!("Expected at least {:} accounts, got {:}.", count, accounts.len())));
+    }
+
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+    use crate::instructions::tests::buy_accounts;
+    use crate::instructions::{buy_ix, collect_creator_fee_ix, create_ix, sell_ix};
+
+    #[test]
+    fn decode_buy() {
+        let accounts = buy_accounts(&TOKEN_2022_PROGRAM_ID);
+        let ix = buy_ix(&accounts, 1_000, 2_000);
+
+        match PumpInstruction::decode(&ix).unwrap() {
+            PumpInstruction::Buy { fee_recipient, mint, bonding_curve, associated_bonding_curve, associated_user, user, creator_vault, token_program, args } => {
+                assert_eq!(fee_recipient, accounts.fee_recipient);
+                assert_eq!(mint, accounts.mint);
+                assert_eq!(bonding_curve, accounts.bonding_curve);
+                assert_eq!(associated_bonding_curve, accounts.associated_bonding_curve);
+                assert_eq!(associated_user, accounts.associated_user);
+                assert_eq!(user, accounts.user);
+                assert_eq!(creator_vault, accounts.creator_vault);
+                assert_eq!(token_program, TOKEN_2022_PROGRAM_ID);
+                assert_eq!(args, BuyArgs { amount: 1_000, max_sol_cost: 2_000 });
+            },
+            other => panic!("Decoded {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_sell() {
+        let accounts = buy_accounts(&TOKEN_2022_PROGRAM_ID);
+        let ix = sell_ix(&accounts.into(), 1_000, 2_000);
+
+        match PumpInstruction::decode(&ix).unwrap() {
+            PumpInstruction::Sell { creator_vault, token_program, user, args, .. } => {
+                assert_eq!(creator_vault, accounts.creator_vault);
+                assert_eq!(token_program, TOKEN_2022_PROGRAM_ID);
+                assert_eq!(user, accounts.user);
+                assert_eq!(args, SellArgs { amount: 1_000, min_sol_output: 2_000 });
+            },
+            other => panic!("Decoded {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_create() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let args = CreateArgs { name: "Pump".to_string(), symbol: "PMP".to_string(), uri: "https://example.com".to_string(), creator: user };
+
+        match PumpInstruction::decode(&create_ix(&mint, &user, &args)).unwrap() {
+            PumpInstruction::Create { mint: decoded_mint, user: decoded_user, args: decoded_args, .. } => {
+                assert_eq!(decoded_mint, mint);
+                assert_eq!(decoded_user, user);
+                assert_eq!(decoded_args.name, args.name);
+                assert_eq!(decoded_args.creator, user);
+            },
+            other => panic!("Decoded {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_compiled_collect_creator_fee() {
+        let creator = Pubkey::new_unique();
+        let ix = collect_creator_fee_ix(&creator);
+
+        let mut account_keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        account_keys.push(PUMPFUN_PROGRAM_ID);
+
+        let compiled = CompiledInstruction {
+            program_id_index: (account_keys.len() - 1) as u8,
+            accounts: (0..ix.accounts.len() as u8).collect(),
+            data: ix.data.clone(),
+        };
+
+        match PumpInstruction::decode_compiled(&compiled, &account_keys).unwrap() {
+            PumpInstruction::CollectCreatorFee { creator: decoded, .. } => assert_eq!(decoded, creator),
+            other => panic!("Decoded {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reject_missing_accounts_and_other_programs() {
+        let mut ix = buy_ix(&buy_accounts(&TOKEN_PROGRAM_ID), 1_000, 2_000);
+        ix.accounts.truncate(9);
+        assert!(matches!(PumpInstruction::decode(&ix), Err(PumpFunError::InvalidInstruction(_))));
+
+        let mut ix = buy_ix(&buy_accounts(&TOKEN_PROGRAM_ID), 1_000, 2_000);
+        ix.program_id = TOKEN_PROGRAM_ID;
+        assert!(matches!(PumpInstruction::decode(&ix), Err(PumpFunError::InvalidInstruction(_))));
+
+        let mut ix = buy_ix(&buy_accounts(&TOKEN_PROGRAM_ID), 1_000, 2_000);
+        ix.data[0] ^= 0xff;
+        assert!(matches!(PumpInstruction::decode(&ix), Err(PumpFunError::InvalidInstruction(_))));
+    }
+}
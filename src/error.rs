@@ -0,0 +1,134 @@
+use std::fmt;
+use solana_client::client_error::reqwest;
+use solana_client::client_error::ClientError;
+use solana_sdk::message::CompileError;
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::pubkey::Pubkey;
+use solana_program::system_instruction::SystemError;
+use solana_sdk::signer::SignerError;
+use spl_token::error::TokenError;
+
+use crate::failure::ProgramFailure;
+
+pub use crate::idl::PumpProgramError;
+
+#[derive(Debug)]
+pub enum PumpFunError {
+    // Boxed, ClientError is large enough to bloat every Result
+    Rpc(Box<ClientError>),
+    Http(reqwest::Error),
+    HttpStatus(reqwest::StatusCode),
+    Deserialization(std::io::Error),
+    Json(serde_json::Error),
+    InvalidAccount(String),
+    InvalidInstruction(String),
+    Compile(CompileError),
+    Signer(SignerError),
+    Transaction(String),
+    // The transaction was rejected, see `failure::decode_transaction_error`
+    Failed(ProgramFailure),
+    CurveComplete(Pubkey),
+    InsufficientLiquidity,
+    InsufficientBalance(ProgramFailure),
+    // The price moved past the slippage limit, TooMuchSolRequired or TooLittleSolReceived
+    SlippageExceeded(PumpProgramError),
+    Program(PumpProgramError),
+}
+
+impl fmt::Display for PumpFunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PumpFunError::Rpc(e) => write!(f, "RPC error: {:}", e),
+            PumpFunError::Http(e) => write!(f, "HTTP error: {:}", e),
+            PumpFunError::HttpStatus(status) => write!(f, "HTTP error: {:}", status),
+            PumpFunError::Deserialization(e) => write!(f, "Deserialization error: {:}", e),
+            PumpFunError::Json(e) => write!(f, "JSON error: {:}", e),
+            PumpFunError::InvalidAccount(e) => write!(f, "Invalid account: {:}", e),
+            PumpFunError::InvalidInstruction(e) => write!(f, "Invalid instruction: {:}", e),
+            PumpFunError::Compile(e) => write!(f, "Transaction error: {:}", e),
+            PumpFunError::Signer(e) => write!(f, "Signer error: {:}", e),
+            PumpFunError::Transaction(e) => write!(f, "Transaction error: {:}", e),
+            PumpFunError::Failed(e) => write!(f, "Transaction failed: {:}", e),
+            PumpFunError::CurveComplete(mint) => write!(f, "Curve of {:} is complete. Trade it on Raydium.", mint),
+            PumpFunError::InsufficientLiquidity => write!(f, "Not enough liquidity on the curve to fill the order."),
+            PumpFunError::InsufficientBalance(e) => write!(f, "Insufficient balance: {:}", e),
+            PumpFunError::SlippageExceeded(e) | PumpFunError::Program(e) => write!(f, "PumpFun error {:} ({:?}): {:}", e.code(), e, e.msg()),
+        }
+    }
+}
+
+impl std::error::Error for PumpFunError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PumpFunError::Rpc(e) => Some(e.as_ref()),
+            PumpFunError::Http(e) => Some(e),
+            PumpFunError::Deserialization(e) => Some(e),
+            PumpFunError::Json(e) => Some(e),
+            PumpFunError::Compile(e) => Some(e),
+            PumpFunError::Signer(e) => Some(e),
+            PumpFunError::Failed(e) | PumpFunError::InsufficientBalance(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<PumpProgramError> for PumpFunError {
+    fn from(e: PumpProgramError) -> Self {
+        match e {
+            PumpProgramError::TooMuchSolRequired | PumpProgramError::TooLittleSolReceived => PumpFunError::SlippageExceeded(e),
+            _ => PumpFunError::Program(e),
+        }
+    }
+}
+
+impl From<ProgramFailure> for PumpFunError {
+    fn from(e: ProgramFailure) -> Self {
+        match e {
+            ProgramFailure::Pump(e) => e.into(),
+            ProgramFailure::System(SystemError::ResultWithNegativeLamports) | ProgramFailure::Token(TokenError::InsufficientFunds) => PumpFunError::InsufficientBalance(e),
+            _ => PumpFunError::Failed(e),
+        }
+    }
+}
+
+impl From<ClientError> for PumpFunError {
+    fn from(e: ClientError) -> Self {
+        PumpFunError::Rpc(Box::new(e))
+    }
+}
+
+impl From<reqwest::Error> for PumpFunError {
+    fn from(e: reqwest::Error) -> Self {
+        PumpFunError::Http(e)
+    }
+}
+
+impl From<std::io::Error> for PumpFunError {
+    fn from(e: std::io::Error) -> Self {
+        PumpFunError::Deserialization(e)
+    }
+}
+
+impl From<serde_json::Error> for PumpFunError {
+    fn from(e: serde_json::Error) -> Self {
+        PumpFunError::Json(e)
+    }
+}
+
+impl From<CompileError> for PumpFunError {
+    fn from(e: CompileError) -> Self {
+        PumpFunError::Compile(e)
+    }
+}
+
+impl From<SignerError> for PumpFunError {
+    fn from(e: SignerError) -> Self {
+        PumpFunError::Signer(e)
+    }
+}
+
+impl From<ProgramError> for PumpFunError {
+    fn from(e: ProgramError) -> Self {
+        PumpFunError::InvalidInstruction(e.to_string())
+    }
+}
@@ -0,0 +1,257 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::PUMPFUN_PROGRAM_ID;
+
+pub use crate::idl::{COLLECT_CREATOR_FEE_EVENT_DISCRIMINATOR, COMPLETE_EVENT_DISCRIMINATOR, CREATE_EVENT_DISCRIMINATOR, SET_PARAMS_EVENT_DISCRIMINATOR, TRADE_EVENT_DISCRIMINATOR};
+
+// Prefix of the self-invoked instruction Anchor uses for emit_cpi!
+pub const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct CreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+    pub creator: Pubkey,
+}
+
+// Events logged before the creator field was added end after `user`, their creator is left as the default key
+impl BorshDeserialize for CreateEvent {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(CreateEvent {
+            name: String::deserialize_reader(reader)?,
+            symbol: String::deserialize_reader(reader)?,
+            uri: String::deserialize_reader(reader)?,
+            mint: Pubkey::deserialize_reader(reader)?,
+            bonding_curve: Pubkey::deserialize_reader(reader)?,
+            user: Pubkey::deserialize_reader(reader)?,
+            creator: Pubkey::deserialize_reader(reader).unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct TradeEvent {
+    pub mint: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: Pubkey,
+    pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+}
+
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct CompleteEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct SetParamsEvent {
+    pub fee_recipient: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+}
+
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct CollectCreatorFeeEvent {
+    pub timestamp: i64,
+    pub creator: Pubkey,
+    pub creator_fee: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum PumpEvent {
+    Create(CreateEvent),
+    Trade(TradeEvent),
+    Complete(CompleteEvent),
+    SetParams(SetParamsEvent),
+    CollectCreatorFee(CollectCreatorFeeEvent),
+}
+
+impl PumpEvent {
+    /// Decodes a discriminator-prefixed event. Fields appended by newer program versions are ignored.
+    pub fn decode(data: &[u8]) -> Option<PumpEvent> {
+
+        if data.len() < 8 {
+            return None;
+        }
+
+        let (discriminator, mut event) = data.split_at(8);
+
+        match discriminator {
+            d if d == CREATE_EVENT_DISCRIMINATOR => CreateEvent::deserialize(&mut event).ok().map(PumpEvent::Create),
+            d if d == TRADE_EVENT_DISCRIMINATOR => TradeEvent::deserialize(&mut event).ok().map(PumpEvent::Trade),
+            d if d == COMPLETE_EVENT_DISCRIMINATOR => CompleteEvent::deserialize(&mut event).ok().map(PumpEvent::Complete),
+            d if d == SET_PARAMS_EVENT_DISCRIMINATOR => SetParamsEvent::deserialize(&mut event).ok().map(PumpEvent::SetParams),
+            d if d == COLLECT_CREATOR_FEE_EVENT_DISCRIMINATOR => CollectCreatorFeeEvent::deserialize(&mut event).ok().map(PumpEvent::CollectCreatorFee),
+            _ => None,
+        }
+    }
+
+    /// Decodes the data of an emit_cpi! inner instruction sent to the program by itself.
+    pub fn decode_cpi(data: &[u8]) -> Option<PumpEvent> {
+
+        if data.len() < 8 || data[..8] != EVENT_IX_TAG {
+            return None;
+        }
+
+        PumpEvent::decode(&data[8..])
+    }
+}
+
+/// Pulls every PumpFun event out of a transaction's log messages.
+/// Only "Program data:" lines logged while the PumpFun program is executing are decoded.
+pub fn parse_logs(logs: &[String]) -> Vec<PumpEvent> {
+    let pumpfun_program_id = PUMPFUN_PROGRAM_ID.to_string();
+
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events: Vec<PumpEvent> = Vec::new();
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if invocations.last() == Some(&pumpfun_program_id.as_str()) {
+                if let Some(event) = STANDARD.decode(data).ok().and_then(|data| PumpEvent::decode(&data)) {
+                    events.push(event);
+                }
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            let program_id = parts.next().unwrap_or_default();
+
+            // "Program log:", "Program return:" and similar lines carry no invocation
+            if program_id.ends_with(':') {
+                continue;
+            }
+
+            match parts.next() {
+                Some("invoke") => invocations.push(program_id),
+                Some("success") | Some("failed:") => {
+                    invocations.pop();
+                },
+                _ => {},
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+    fn trade_event(sol_amount: u64) -> TradeEvent {
+        TradeEvent {
+            mint: Pubkey::new_unique(),
+            sol_amount,
+            token_amount: 3_563_816_218_171,
+            is_buy: true,
+            user: Pubkey::new_unique(),
+            timestamp: 1_735_689_600,
+            virtual_sol_reserves: 30_100_000_000,
+            virtual_token_reserves: 1_069_436_183_781_829,
+        }
+    }
+
+    fn data_line<T: BorshSerialize>(discriminator: [u8; 8], event: &T) -> String {
+        let mut data: Vec<u8> = discriminator.to_vec();
+        data.extend_from_slice(&borsh::to_vec(event).unwrap());
+        format!("Program data: {:}", STANDARD.encode(data))
+    }
+
+    #[test]
+    fn parse_logs_of_nested_invocations() {
+        let pumpfun = PUMPFUN_PROGRAM_ID.to_string();
+        let token = TOKEN_PROGRAM_ID.to_string();
+        let system = SYSTEM_PROGRAM_ID.to_string();
+
+        let logs: Vec<String> = vec![
+            format!("Program {:} invoke [1]", pumpfun),
+            "Program log: Instruction: Buy".to_string(),
+            format!("Program {:} invoke [2]", token),
+            // Logged by the token program, not by PumpFun
+            data_line(TRADE_EVENT_DISCRIMINATOR, &trade_event(1)),
+            format!("Program {:} consumed 4645 of 180000 compute units", token),
+            format!("Program {:} success", token),
+            format!("Program {:} invoke [2]", system),
+            format!("Program {:} success", system),
+            data_line(TRADE_EVENT_DISCRIMINATOR, &trade_event(100_000_000)),
+            format!("Program {:} success", pumpfun),
+            format!("Program {:} invoke [1]", pumpfun),
+            format!("Program {:} invoke [2]", token),
+            format!("Program {:} failed: custom program error: 0x1", token),
+            data_line(TRADE_EVENT_DISCRIMINATOR, &trade_event(200_000_000)),
+            format!("Program {:} failed: custom program error: 0x1", pumpfun),
+            data_line(TRADE_EVENT_DISCRIMINATOR, &trade_event(3)),
+        ];
+
+        let events = parse_logs(&logs);
+
+        assert_eq!(events.len(), 2);
+
+        match (&events[0], &events[1]) {
+            (PumpEvent::Trade(first), PumpEvent::Trade(second)) => {
+                assert_eq!(first.sol_amount, 100_000_000);
+                assert_eq!(second.sol_amount, 200_000_000);
+            },
+            other => panic!("Parsed {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_cpi_event() {
+        let mut data: Vec<u8> = EVENT_IX_TAG.to_vec();
+        data.extend_from_slice(&TRADE_EVENT_DISCRIMINATOR);
+        data.extend_from_slice(&borsh::to_vec(&trade_event(42)).unwrap());
+
+        assert!(matches!(PumpEvent::decode_cpi(&data), Some(PumpEvent::Trade(event)) if event.sol_amount == 42));
+        assert!(PumpEvent::decode_cpi(&data[8..]).is_none());
+    }
+
+    #[test]
+    fn decode_create_event_with_and_without_creator() {
+        let event = CreateEvent {
+            name: "Pump".to_string(),
+            symbol: "PMP".to_string(),
+            uri: "https://example.com".to_string(),
+            mint: Pubkey::new_unique(),
+            bonding_curve: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+        };
+
+        let mut data: Vec<u8> = CREATE_EVENT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&borsh::to_vec(&event).unwrap());
+
+        match PumpEvent::decode(&data) {
+            Some(PumpEvent::Create(decoded)) => assert_eq!(decoded.creator, event.creator),
+            other => panic!("Decoded {:?}", other),
+        }
+
+        // Events logged before the creator field was added
+        data.truncate(data.len() - 32);
+
+        match PumpEvent::decode(&data) {
+            Some(PumpEvent::Create(decoded)) => {
+                assert_eq!(decoded.user, event.user);
+                assert_eq!(decoded.creator, Pubkey::default());
+            },
+            other => panic!("Decoded {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_program::decode_error::DecodeError;
+use solana_program::instruction::InstructionError;
+use solana_program::system_instruction::SystemError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+use spl_token::error::TokenError;
+
+use crate::constants::{PUMPFUN_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crate::error::PumpProgramError;
+
+// Why a transaction was rejected, resolved to the program that raised the error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgramFailure {
+    Pump(PumpProgramError),
+    System(SystemError),
+    Token(TokenError),
+    Instruction { program_id: Option<Pubkey>, error: InstructionError },
+    Transaction(TransactionError),
+}
+
+impl ProgramFailure {
+    pub fn reason(&self) -> String {
+        match self {
+            ProgramFailure::Pump(e) => format!("PumpFun {:?}: {:}", e, e.msg()),
+            ProgramFailure::System(e) => format!("System program {:?}: {:}", e, e),
+            ProgramFailure::Token(e) => format!("Token program {:?}: {:}", e, e),
+            ProgramFailure::Instruction { program_id: Some(program_id), error } => format!("Program {:}: {:}", program_id, error),
+            ProgramFailure::Instruction { program_id: None, error } => error.to_string(),
+            ProgramFailure::Transaction(e) => e.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ProgramFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:}", self.reason())
+    }
+}
+
+impl std::error::Error for ProgramFailure {}
+
+/// Decodes a failed transaction. `program_ids` are the program ids of the transaction's
+/// instructions in order, `logs` the simulation or execution logs when available.
+pub fn decode_transaction_error(err: &TransactionError, program_ids: &[Pubkey], logs: &[String]) -> ProgramFailure {
+
+    let (index, error) = match err {
+        TransactionError::InstructionError(index, error) => (*index as usize, error),
+        _ => return ProgramFailure::Transaction(err.clone()),
+    };
+
+    // Errors raised in a CPI are reported against the top level instruction, the logs name the program
+    let program_id = failed_program(logs).or_else(|| program_ids.get(index).copied());
+
+    if let InstructionError::Custom(code) = error {
+        let failure = match program_id {
+            Some(id) if id == PUMPFUN_PROGRAM_ID => PumpProgramError::from_code(*code).map(ProgramFailure::Pump),
+            Some(id) if id == SYSTEM_PROGRAM_ID => SystemError::decode_custom_error_to_enum(*code).map(ProgramFailure::System),
+            // Token-2022 shares the SPL Token error codes and appends its own
+            Some(id) if id == TOKEN_PROGRAM_ID || id == TOKEN_2022_PROGRAM_ID => TokenError::decode_custom_error_to_enum(*code).map(ProgramFailure::Token),
+            _ => None,
+        };
+
+        if let Some(failure) = failure {
+            return failure;
+        }
+    }
+
+    ProgramFailure::Instruction { program_id, error: error.clone() }
+}
+
+/// Decodes the transaction failure carried by an RPC error, e.g. a failed preflight simulation.
+/// Returns `None` when the error did not come from executing the transaction.
+pub fn decode_client_error(err: &ClientError, program_ids: &[Pubkey]) -> Option<ProgramFailure> {
+
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { data: RpcResponseErrorData::SendTransactionPreflightFailure(result), .. }) => {
+            let logs = result.logs.as_deref().unwrap_or_default();
+            result.err.as_ref().map(|err| decode_transaction_error(err, program_ids, logs))
+        },
+        ClientErrorKind::TransactionError(err) => Some(decode_transaction_error(err, program_ids, &[])),
+        _ => None,
+    }
+}
+
+// The innermost failing program logs "Program <id> failed: ..." first
+fn failed_program(logs: &[String]) -> Option<Pubkey> {
+
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program "))
+        .find_map(|rest| {
+            let mut parts = rest.split_whitespace();
+            let program_id = parts.next()?;
+
+            match parts.next() {
+                Some("failed:") => program_id.parse().ok(),
+                _ => None,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::compute_budget;
+
+    #[test]
+    fn decode_pump_error() {
+        let err = TransactionError::InstructionError(1, InstructionError::Custom(6002));
+        let failure = decode_transaction_error(&err, &[compute_budget::id(), PUMPFUN_PROGRAM_ID], &[]);
+
+        assert_eq!(failure, ProgramFailure::Pump(PumpProgramError::TooMuchSolRequired));
+    }
+
+    #[test]
+    fn decode_cpi_failure_reported_against_outer_instruction() {
+        // The token program failed inside the PumpFun instruction at index 1
+        let logs: Vec<String> = vec![
+            format!("Program {:} invoke [1]", PUMPFUN_PROGRAM_ID),
+            "Program log: Instruction: Sell".to_string(),
+            format!("Program {:} invoke [2]", TOKEN_PROGRAM_ID),
+            "Program log: Error: insufficient funds".to_string(),
+            format!("Program {:} failed: custom program error: 0x1", TOKEN_PROGRAM_ID),
+            format!("Program {:} failed: custom program error: 0x1", PUMPFUN_PROGRAM_ID),
+        ];
+
+        let err = TransactionError::InstructionError(1, InstructionError::Custom(1));
+        let failure = decode_transaction_error(&err, &[compute_budget::id(), PUMPFUN_PROGRAM_ID], &logs);
+
+        assert_eq!(failure, ProgramFailure::Token(TokenError::InsufficientFunds));
+
+        // Without logs the error is attributed to the outer program, where 1 is not a PumpFun code
+        let failure = decode_transaction_error(&err, &[compute_budget::id(), PUMPFUN_PROGRAM_ID], &[]);
+
+        assert_eq!(failure, ProgramFailure::Instruction { program_id: Some(PUMPFUN_PROGRAM_ID), error: InstructionError::Custom(1) });
+    }
+
+    #[test]
+    fn decode_system_and_transaction_errors() {
+        let err = TransactionError::InstructionError(0, InstructionError::Custom(1));
+        let failure = decode_transaction_error(&err, &[SYSTEM_PROGRAM_ID], &[]);
+
+        assert_eq!(failure, ProgramFailure::System(SystemError::ResultWithNegativeLamports));
+
+        let failure = decode_transaction_error(&TransactionError::BlockhashNotFound, &[PUMPFUN_PROGRAM_ID], &[]);
+
+        assert_eq!(failure, ProgramFailure::Transaction(TransactionError::BlockhashNotFound));
+    }
+}
@@ -0,0 +1,136 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::{GLOBAL_ACCOUNT_DISCRIMINATOR, PUMPFUN_GLOBAL, PUMPFUN_PROGRAM_ID};
+use crate::error::PumpFunError;
+
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct GlobalState {
+    _signature: [u8; 8],                // [0xa7, 0xe8, 0xe8, 0xb1, 0xc8, 0x6c, 0x72, 0x7f]
+    pub initialized: bool,
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+    // Appended by the creator fee upgrade, zero when read from an older account
+    #[borsh(skip)]
+    pub withdraw_authority: Pubkey,
+    #[borsh(skip)]
+    pub enable_migrate: bool,
+    #[borsh(skip)]
+    pub pool_migration_fee: u64,
+    #[borsh(skip)]
+    pub creator_fee_basis_points: u64,
+}
+
+#[derive(BorshDeserialize)]
+struct GlobalExtension {
+    withdraw_authority: Pubkey,
+    enable_migrate: bool,
+    pool_migration_fee: u64,
+    creator_fee_basis_points: u64,
+}
+
+impl GlobalState {
+    const V1_LEN: usize = 113;
+
+    pub fn from_account_data(account_data: &[u8]) -> Result<GlobalState, PumpFunError> {
+
+        if account_data.len() < 8 || account_data[..8] != GLOBAL_ACCOUNT_DISCRIMINATOR {
+            return Err(PumpFunError::InvalidAccount("Account is not a PumpFun Global account.".to_string()));
+        }
+
+        // The program appends new fields to the end of the account, only read the known prefix
+        let mut global_state = GlobalState::deserialize(&mut &account_data[..])?;
+
+        if account_data.len() > GlobalState::V1_LEN {
+            let extension = GlobalExtension::deserialize(&mut &account_data[GlobalState::V1_LEN..])?;
+
+            global_state.withdraw_authority = extension.withdraw_authority;
+            global_state.enable_migrate = extension.enable_migrate;
+            global_state.pool_migration_fee = extension.pool_migration_fee;
+            global_state.creator_fee_basis_points = extension.creator_fee_basis_points;
+        }
+
+        Ok(global_state)
+    }
+}
+
+pub async fn get_global_state(client: &RpcClient) -> Result<GlobalState, PumpFunError> {
+
+    let account = client.get_account(&PUMPFUN_GLOBAL).await?;
+
+    if account.owner != PUMPFUN_PROGRAM_ID {
+        return Err(PumpFunError::InvalidAccount(format!("Global account {:} is not owned by the PumpFun program.", PUMPFUN_GLOBAL)));
+    }
+
+    GlobalState::from_account_data(&account.data)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn v1_account_data() -> Vec<u8> {
+        let mut data: Vec<u8> = GLOBAL_ACCOUNT_DISCRIMINATOR.to_vec();
+        data.push(1);
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(PUMPFUN_PROGRAM_ID.as_ref());
+
+        for value in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000, 95] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        data
+    }
+
+    #[test]
+    fn decode_v1_account() {
+        let data = v1_account_data();
+        assert_eq!(data.len(), GlobalState::V1_LEN);
+
+        let global = GlobalState::from_account_data(&data).unwrap();
+
+        assert!(global.initialized);
+        assert_eq!(global.fee_recipient, PUMPFUN_PROGRAM_ID);
+        assert_eq!(global.initial_virtual_sol_reserves, 30_000_000_000);
+        assert_eq!(global.fee_basis_points, 95);
+        assert_eq!(global.withdraw_authority, Pubkey::default());
+        assert!(!global.enable_migrate);
+        assert_eq!(global.creator_fee_basis_points, 0);
+    }
+
+    #[test]
+    fn decode_extended_account() {
+        let withdraw_authority = Pubkey::new_unique();
+
+        let mut data = v1_account_data();
+        data.extend_from_slice(withdraw_authority.as_ref());
+        data.push(1);
+        data.extend_from_slice(&15_000_000u64.to_le_bytes());
+        data.extend_from_slice(&5u64.to_le_bytes());
+        // Fields added by later upgrades are ignored
+        data.extend_from_slice(&[0xff; 64]);
+
+        let global = GlobalState::from_account_data(&data).unwrap();
+
+        assert_eq!(global.fee_basis_points, 95);
+        assert_eq!(global.withdraw_authority, withdraw_authority);
+        assert!(global.enable_migrate);
+        assert_eq!(global.pool_migration_fee, 15_000_000);
+        assert_eq!(global.creator_fee_basis_points, 5);
+    }
+
+    #[test]
+    fn reject_other_accounts() {
+        let mut data = v1_account_data();
+        data[0] ^= 0xff;
+
+        assert!(matches!(GlobalState::from_account_data(&data), Err(PumpFunError::InvalidAccount(_))));
+        assert!(matches!(GlobalState::from_account_data(&data[..4]), Err(PumpFunError::InvalidAccount(_))));
+    }
+}
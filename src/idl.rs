@@ -0,0 +1,24 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+// Discriminators, argument structs and error codes generated by build.rs from idl/pump.json
+include!(concat!(env!("OUT_DIR"), "/pump_idl.rs"));
+
+// Fail the build if the bundled IDL ever drifts from the deployed program
+const _: () = assert!(u64::from_be_bytes(INITIALIZE_DISCRIMINATOR) == 0xafaf6d1f0d989bed);
+const _: () = assert!(u64::from_be_bytes(SET_PARAMS_DISCRIMINATOR) == 0x1beab2349302bb8d);
+const _: () = assert!(u64::from_be_bytes(CREATE_DISCRIMINATOR) == 0x181ec828051c0777);
+const _: () = assert!(u64::from_be_bytes(BUY_DISCRIMINATOR) == 0x66063d1201daebea);
+const _: () = assert!(u64::from_be_bytes(SELL_DISCRIMINATOR) == 0x33e685a4017f83ad);
+const _: () = assert!(u64::from_be_bytes(WITHDRAW_DISCRIMINATOR) == 0xb712469c946da122);
+const _: () = assert!(u64::from_be_bytes(COLLECT_CREATOR_FEE_DISCRIMINATOR) == 0x1416567bc61cdb84);
+const _: () = assert!(u64::from_be_bytes(GLOBAL_ACCOUNT_DISCRIMINATOR) == 0xa7e8e8b1c86c727f);
+const _: () = assert!(u64::from_be_bytes(BONDING_CURVE_ACCOUNT_DISCRIMINATOR) == 0x17b7f83760d8ac60);
+const _: () = assert!(u64::from_be_bytes(CREATE_EVENT_DISCRIMINATOR) == 0x1b72a94ddeeb6376);
+const _: () = assert!(u64::from_be_bytes(TRADE_EVENT_DISCRIMINATOR) == 0xbddb7fd34ee661ee);
+const _: () = assert!(u64::from_be_bytes(COMPLETE_EVENT_DISCRIMINATOR) == 0x5f72619cd42e9808);
+const _: () = assert!(u64::from_be_bytes(SET_PARAMS_EVENT_DISCRIMINATOR) == 0xdfc39ff63e308f83);
+const _: () = assert!(u64::from_be_bytes(COLLECT_CREATOR_FEE_EVENT_DISCRIMINATOR) == 0x7a027f010ebf0caf);
+
+const _: () = assert!(PumpProgramError::TooMuchSolRequired as u32 == 6002);
+const _: () = assert!(PumpProgramError::TooLittleSolReceived as u32 == 6003);
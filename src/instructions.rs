@@ -1,37 +1,222 @@
+use borsh::BorshSerialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 
 use solana_program::instruction::{AccountMeta, Instruction};
-use crate::constants::{ASSOC_TOKEN_ACC_PROGRAM_ID, EVENT_AUTHORITY, PUMPFUN_FEE_RECIPIENT, PUMPFUN_GLOBAL, PUMPFUN_PROGRAM_ID, SYSTEM_RENT_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 
+use crate::constants::{ASSOC_TOKEN_ACC_PROGRAM_ID, EVENT_AUTHORITY, MPL_TOKEN_METADATA_PROGRAM_ID, PUMPFUN_GLOBAL, PUMPFUN_PROGRAM_ID, SYSTEM_RENT_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crate::curve::{derive_bonding_curve, derive_bonding_curve_accounts, get_bonding_curve_account, BondingCurveAccount};
+use crate::error::PumpFunError;
+use crate::global::{get_global_state, GlobalState};
 
+pub use crate::idl::{BuyArgs, CreateArgs, SellArgs, SetParamsArgs};
+pub use crate::idl::{BUY_DISCRIMINATOR, COLLECT_CREATOR_FEE_DISCRIMINATOR, CREATE_DISCRIMINATOR, INITIALIZE_DISCRIMINATOR, SELL_DISCRIMINATOR, SET_PARAMS_DISCRIMINATOR, WITHDRAW_DISCRIMINATOR};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuyAccounts {
+    pub fee_recipient: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub user: Pubkey,
+    pub associated_user: Pubkey,
+    pub creator_vault: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl BuyAccounts {
+    /// `curve` provides the creator vault, `token_program` is the owner of the mint account,
+    /// either the SPL Token or the Token-2022 program. See `resolve` to fetch all three.
+    pub fn from_state(mint: &Pubkey, user: &Pubkey, global: &GlobalState, curve: &BondingCurveAccount, token_program: &Pubkey) -> BuyAccounts {
+        // Curves created before the creator field was added pay creator fees to the default vault
+        BuyAccounts::new(mint, user, &global.fee_recipient, &curve.creator().unwrap_or_default(), token_program)
+    }
+
+    /// Fetches the Global account, the bonding curve and the mint to fill in the fee recipient, creator vault and token program.
+    pub async fn resolve(client: &RpcClient, mint: &Pubkey, user: &Pubkey) -> Result<BuyAccounts, PumpFunError> {
+        let global = get_global_state(client).await?;
+        let curve = get_bonding_curve_account(client, &derive_bonding_curve(mint)).await?;
+        let token_program = get_token_program(client, mint).await?;
+
+        Ok(BuyAccounts::from_state(mint, user, &global, &curve, &token_program))
+    }
+
+    fn new(mint: &Pubkey, user: &Pubkey, fee_recipient: &Pubkey, creator: &Pubkey, token_program: &Pubkey) -> BuyAccounts {
+        let (bonding_curve, associated_bonding_curve) = derive_bonding_curve_accounts(mint, token_program);
+
+        BuyAccounts {
+            fee_recipient: *fee_recipient,
+            mint: *mint,
+            bonding_curve,
+            associated_bonding_curve,
+            user: *user,
+            associated_user: get_associated_token_address_with_program_id(user, mint, token_program),
+            creator_vault: derive_creator_vault(creator),
+            token_program: *token_program,
+        }
+    }
+}
+
+
+// Sell takes the same accounts as buy, the program lists the creator vault and token program the other way round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SellAccounts {
+    pub fee_recipient: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub user: Pubkey,
+    pub associated_user: Pubkey,
+    pub creator_vault: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl SellAccounts {
+    /// See `BuyAccounts::from_state`.
+    pub fn from_state(mint: &Pubkey, user: &Pubkey, global: &GlobalState, curve: &BondingCurveAccount, token_program: &Pubkey) -> SellAccounts {
+        BuyAccounts::from_state(mint, user, global, curve, token_program).into()
+    }
+
+    /// See `BuyAccounts::resolve`.
+    pub async fn resolve(client: &RpcClient, mint: &Pubkey, user: &Pubkey) -> Result<SellAccounts, PumpFunError> {
+        Ok(BuyAccounts::resolve(client, mint, user).await?.into())
+    }
+}
+
+impl From<BuyAccounts> for SellAccounts {
+    fn from(accounts: BuyAccounts) -> SellAccounts {
+        SellAccounts {
+            fee_recipient: accounts.fee_recipient,
+            mint: accounts.mint,
+            bonding_curve: accounts.bonding_curve,
+            associated_bonding_curve: accounts.associated_bonding_curve,
+            user: accounts.user,
+            associated_user: accounts.associated_user,
+            creator_vault: accounts.creator_vault,
+            token_program: accounts.token_program,
+        }
+    }
+}
+
+/// Returns the program owning the mint, either the SPL Token or the Token-2022 program.
+pub async fn get_token_program(client: &RpcClient, mint: &Pubkey) -> Result<Pubkey, PumpFunError> {
+
+    let account = client.get_account(mint).await?;
+
+    if account.owner != TOKEN_PROGRAM_ID && account.owner != TOKEN_2022_PROGRAM_ID {
+        return Err(PumpFunError::InvalidAccount(format!("Mint {:} is not owned by a token program.", mint)));
+    }
+
+    Ok(account.owner)
+}
+
+
+pub fn derive_mint_authority() -> Pubkey {
+
+    let (mint_authority, _) = Pubkey::find_program_address(
+        &["mint-authority".as_bytes()],
+        &PUMPFUN_PROGRAM_ID
+    );
+
+    mint_authority
+}
+
+pub fn derive_metadata_account(mint: &Pubkey) -> Pubkey {
+
+    let (metadata, _) = Pubkey::find_program_address(
+        &["metadata".as_bytes(), MPL_TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &MPL_TOKEN_METADATA_PROGRAM_ID
+    );
+
+    metadata
+}
+
+pub fn derive_creator_vault(creator: &Pubkey) -> Pubkey {
+
+    let (creator_vault, _) = Pubkey::find_program_address(
+        &["creator-vault".as_bytes(), creator.as_ref()],
+        &PUMPFUN_PROGRAM_ID
+    );
+
+    creator_vault
+}
+
+fn instruction_data<T: BorshSerialize>(discriminator: [u8; 8], args: &T) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::new();
+    data.extend_from_slice(&discriminator);
+    data.extend_from_slice(&borsh::to_vec(args).unwrap());
+    data
+}
+
+
+pub fn create_ix(
+    mint: &Pubkey,
+    user: &Pubkey,
+    args: &CreateArgs) -> Instruction {
+
+    let (bonding_curve, associated_bonding_curve) = derive_bonding_curve_accounts(mint, &TOKEN_PROGRAM_ID);
+
+    let accounts = vec![
+        AccountMeta::new(*mint, true),
+        AccountMeta::new_readonly(derive_mint_authority(), false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(associated_bonding_curve, false),
+        AccountMeta::new_readonly(PUMPFUN_GLOBAL, false),
+        AccountMeta::new_readonly(MPL_TOKEN_METADATA_PROGRAM_ID, false),
+        AccountMeta::new(derive_metadata_account(mint), false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOC_TOKEN_ACC_PROGRAM_ID, false),
+        AccountMeta::new_readonly(SYSTEM_RENT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(PUMPFUN_PROGRAM_ID, false)
+    ];
+
+    let data = instruction_data(CREATE_DISCRIMINATOR, args);
+
+    Instruction {
+        program_id: PUMPFUN_PROGRAM_ID,
+        accounts,
+        data
+    }
+}
+
+
+#[allow(clippy::too_many_arguments)]
 pub fn buy_amount_out_ix(
+    fee_recipient: &Pubkey,
     mint: &Pubkey,
     bonding_curve: &Pubkey,
     associated_bonding_curve: &Pubkey,
     wallet: &Pubkey,
     associated_token_account: &Pubkey,
+    creator_vault: &Pubkey,
+    token_program: &Pubkey,
     amount_out: u64,
     max_amount_in_sol: u64) -> Instruction {
 
     let accounts = vec![
         AccountMeta::new_readonly(PUMPFUN_GLOBAL, false),
-        AccountMeta::new(PUMPFUN_FEE_RECIPIENT, false),
+        AccountMeta::new(*fee_recipient, false),
         AccountMeta::new_readonly(*mint, false),
         AccountMeta::new(*bonding_curve, false),
         AccountMeta::new(*associated_bonding_curve, false),
         AccountMeta::new(*associated_token_account, false),
         AccountMeta::new(*wallet, true),
         AccountMeta::new_readonly(solana_program::system_program::id(), false),
-        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-        AccountMeta::new_readonly(SYSTEM_RENT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*token_program, false),
+        AccountMeta::new(*creator_vault, false),
         AccountMeta::new_readonly(EVENT_AUTHORITY, false),
         AccountMeta::new_readonly(PUMPFUN_PROGRAM_ID, false)
     ];
 
-    let mut data: Vec<u8> = Vec::new();
-    data.extend_from_slice(&[0x66, 0x06, 0x3d, 0x12, 0x01, 0xda, 0xeb, 0xea]);
-    data.extend_from_slice(&amount_out.to_le_bytes());
-    data.extend_from_slice(&max_amount_in_sol.to_le_bytes());
+    let data = instruction_data(BUY_DISCRIMINATOR, &BuyArgs {
+        amount: amount_out,
+        max_sol_cost: max_amount_in_sol,
+    });
 
     Instruction {
         program_id: PUMPFUN_PROGRAM_ID,
@@ -41,35 +226,53 @@ pub fn buy_amount_out_ix(
 }
 
 
+pub fn buy_ix(accounts: &BuyAccounts, amount_out: u64, max_amount_in_sol: u64) -> Instruction {
+    buy_amount_out_ix(
+        &accounts.fee_recipient,
+        &accounts.mint,
+        &accounts.bonding_curve,
+        &accounts.associated_bonding_curve,
+        &accounts.user,
+        &accounts.associated_user,
+        &accounts.creator_vault,
+        &accounts.token_program,
+        amount_out,
+        max_amount_in_sol)
+}
+
+
+#[allow(clippy::too_many_arguments)]
 pub fn sell_amount_in_ix(
+    fee_recipient: &Pubkey,
     mint: &Pubkey,
     bonding_curve: &Pubkey,
     associated_bonding_curve: &Pubkey,
     wallet: &Pubkey,
     associated_token_account: &Pubkey,
+    creator_vault: &Pubkey,
+    token_program: &Pubkey,
     amount_in: u64,
     min_amount_out_sol: u64) -> Instruction {
 
     let accounts = vec![
         AccountMeta::new_readonly(PUMPFUN_GLOBAL, false),
-        AccountMeta::new(PUMPFUN_FEE_RECIPIENT, false),
+        AccountMeta::new(*fee_recipient, false),
         AccountMeta::new_readonly(*mint, false),
         AccountMeta::new(*bonding_curve, false),
         AccountMeta::new(*associated_bonding_curve, false),
         AccountMeta::new(*associated_token_account, false),
         AccountMeta::new(*wallet, true),
         AccountMeta::new_readonly(solana_program::system_program::id(), false),
-        AccountMeta::new_readonly(ASSOC_TOKEN_ACC_PROGRAM_ID, false),
-        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(*creator_vault, false),
+        AccountMeta::new_readonly(*token_program, false),
         AccountMeta::new_readonly(EVENT_AUTHORITY, false),
         AccountMeta::new_readonly(PUMPFUN_PROGRAM_ID, false)
     ];
 
-    let mut data: Vec<u8> = Vec::new();
-    // 33e685a4017f83ad
-    data.extend_from_slice(&[0x33, 0xe6, 0x85, 0xa4, 0x01, 0x7f, 0x83, 0xad]);
-    data.extend_from_slice(&amount_in.to_le_bytes());
-    data.extend_from_slice(&min_amount_out_sol.to_le_bytes());
+    let data = instruction_data(SELL_DISCRIMINATOR, &SellArgs {
+        amount: amount_in,
+        min_sol_output: min_amount_out_sol,
+    });
 
     Instruction {
         program_id: PUMPFUN_PROGRAM_ID,
@@ -77,3 +280,203 @@ pub fn sell_amount_in_ix(
         data
     }
 }
+
+
+pub fn sell_ix(accounts: &SellAccounts, amount_in: u64, min_amount_out_sol: u64) -> Instruction {
+    sell_amount_in_ix(
+        &accounts.fee_recipient,
+        &accounts.mint,
+        &accounts.bonding_curve,
+        &accounts.associated_bonding_curve,
+        &accounts.user,
+        &accounts.associated_user,
+        &accounts.creator_vault,
+        &accounts.token_program,
+        amount_in,
+        min_amount_out_sol)
+}
+
+
+pub fn collect_creator_fee_ix(creator: &Pubkey) -> Instruction {
+
+    let accounts = vec![
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(derive_creator_vault(creator), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(PUMPFUN_PROGRAM_ID, false)
+    ];
+
+    Instruction {
+        program_id: PUMPFUN_PROGRAM_ID,
+        accounts,
+        data: COLLECT_CREATOR_FEE_DISCRIMINATOR.to_vec()
+    }
+}
+
+
+pub fn initialize_ix(authority: &Pubkey) -> Instruction {
+
+    let accounts = vec![
+        AccountMeta::new(PUMPFUN_GLOBAL, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false)
+    ];
+
+    Instruction {
+        program_id: PUMPFUN_PROGRAM_ID,
+        accounts,
+        data: INITIALIZE_DISCRIMINATOR.to_vec()
+    }
+}
+
+
+pub fn set_params_ix(authority: &Pubkey, params: &SetParamsArgs) -> Instruction {
+
+    let accounts = vec![
+        AccountMeta::new(PUMPFUN_GLOBAL, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(PUMPFUN_PROGRAM_ID, false)
+    ];
+
+    Instruction {
+        program_id: PUMPFUN_PROGRAM_ID,
+        accounts,
+        data: instruction_data(SET_PARAMS_DISCRIMINATOR, params)
+    }
+}
+
+
+pub fn withdraw_ix(
+    mint: &Pubkey,
+    authority: &Pubkey,
+    associated_token_account: &Pubkey) -> Instruction {
+
+    let (bonding_curve, associated_bonding_curve) = derive_bonding_curve_accounts(mint, &TOKEN_PROGRAM_ID);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(PUMPFUN_GLOBAL, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(associated_bonding_curve, false),
+        AccountMeta::new(*associated_token_account, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(SYSTEM_RENT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(PUMPFUN_PROGRAM_ID, false)
+    ];
+
+    Instruction {
+        program_id: PUMPFUN_PROGRAM_ID,
+        accounts,
+        data: WITHDRAW_DISCRIMINATOR.to_vec()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::curve::CurveState;
+
+    pub(crate) fn buy_accounts(token_program: &Pubkey) -> BuyAccounts {
+        BuyAccounts::new(&Pubkey::new_unique(), &Pubkey::new_unique(), &Pubkey::new_unique(), &Pubkey::new_unique(), token_program)
+    }
+
+    #[test]
+    fn create_ix_encodes_args_and_accounts() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let args = CreateArgs {
+            name: "Pump".to_string(),
+            symbol: "PMP".to_string(),
+            uri: "https://example.com/pump.json".to_string(),
+            creator: user,
+        };
+
+        let ix = create_ix(&mint, &user, &args);
+
+        let mut data: Vec<u8> = Vec::new();
+        data.extend_from_slice(&CREATE_DISCRIMINATOR);
+
+        for field in [&args.name, &args.symbol, &args.uri] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+
+        data.extend_from_slice(args.creator.as_ref());
+
+        assert_eq!(ix.data, data);
+
+        let (bonding_curve, associated_bonding_curve) = derive_bonding_curve_accounts(&mint, &TOKEN_PROGRAM_ID);
+        let signers: Vec<Pubkey> = ix.accounts.iter().filter(|meta| meta.is_signer).map(|meta| meta.pubkey).collect();
+
+        assert_eq!(signers, vec![mint, user]);
+        assert_eq!(ix.accounts[2].pubkey, bonding_curve);
+        assert_eq!(ix.accounts[3].pubkey, associated_bonding_curve);
+        assert_eq!(ix.accounts[6].pubkey, derive_metadata_account(&mint));
+    }
+
+    #[test]
+    fn buy_args_match_manual_encoding() {
+        let ix = buy_ix(&buy_accounts(&TOKEN_PROGRAM_ID), 1_234_567, 89_000_000);
+
+        let mut data: Vec<u8> = Vec::new();
+        data.extend_from_slice(&BUY_DISCRIMINATOR);
+        data.extend_from_slice(&1_234_567u64.to_le_bytes());
+        data.extend_from_slice(&89_000_000u64.to_le_bytes());
+
+        assert_eq!(ix.data, data);
+    }
+
+    #[test]
+    fn sell_args_match_manual_encoding() {
+        let ix = sell_ix(&buy_accounts(&TOKEN_PROGRAM_ID).into(), 1_234_567, 89_000_000);
+
+        let mut data: Vec<u8> = Vec::new();
+        data.extend_from_slice(&SELL_DISCRIMINATOR);
+        data.extend_from_slice(&1_234_567u64.to_le_bytes());
+        data.extend_from_slice(&89_000_000u64.to_le_bytes());
+
+        assert_eq!(ix.data, data);
+    }
+
+    #[test]
+    fn accounts_from_state() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+
+        let global = GlobalState::from_account_data(&crate::global::tests::v1_account_data()).unwrap();
+        let curve = BondingCurveAccount::V2 { state: CurveState::from_global(&global), creator };
+
+        let accounts = BuyAccounts::from_state(&mint, &user, &global, &curve, &TOKEN_2022_PROGRAM_ID);
+
+        assert_eq!(accounts.fee_recipient, global.fee_recipient);
+        assert_eq!(accounts.bonding_curve, derive_bonding_curve(&mint));
+        assert_eq!(accounts.associated_user, get_associated_token_address_with_program_id(&user, &mint, &TOKEN_2022_PROGRAM_ID));
+        assert_eq!(accounts.creator_vault, derive_creator_vault(&creator));
+        assert_eq!(accounts.token_program, TOKEN_2022_PROGRAM_ID);
+
+        // Curves without a creator pay into the default vault
+        let curve = BondingCurveAccount::V1(CurveState::from_global(&global));
+        let accounts = SellAccounts::from_state(&mint, &user, &global, &curve, &TOKEN_PROGRAM_ID);
+
+        assert_eq!(accounts.creator_vault, derive_creator_vault(&Pubkey::default()));
+        assert_eq!(accounts.associated_user, get_associated_token_address_with_program_id(&user, &mint, &TOKEN_PROGRAM_ID));
+    }
+
+    #[test]
+    fn sell_accounts_from_buy_accounts() {
+        let buy = buy_accounts(&TOKEN_PROGRAM_ID);
+        let sell = SellAccounts::from(buy);
+
+        assert_eq!(sell.fee_recipient, buy.fee_recipient);
+        assert_eq!(sell.creator_vault, buy.creator_vault);
+        assert_eq!(sell.token_program, buy.token_program);
+        assert_eq!(sell.associated_user, buy.associated_user);
+    }
+}
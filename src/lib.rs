@@ -2,73 +2,145 @@ use std::collections::HashMap;
 
 use solana_sdk::pubkey::Pubkey;
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_client::rpc_client::SerializableTransaction;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
 use solana_program::hash::Hash;
 use solana_program::instruction::Instruction;
 use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::signature::{Keypair, Signature, Signer};
-use solana_sdk::transaction::Transaction;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use solana_program::address_lookup_table::AddressLookupTableAccount;
 use spl_associated_token_account::{get_associated_token_address_with_program_id, instruction};
-use spl_token::instruction::close_account;
+use spl_token_2022::instruction::close_account;
 
-use crate::curve::{derive_bonding_curve_accounts, get_bonding_curve_state};
-use crate::instructions::{buy_amount_out_ix, sell_amount_in_ix};
+use crate::error::PumpFunError;
+use crate::failure::{decode_client_error, decode_transaction_error};
+use crate::curve::{derive_bonding_curve, get_bonding_curve_account, get_bonding_curve_state, get_bonding_curve_states, sol_limit_with_slippage, BondingCurveAccount, CurveState, FeeRates, QuoteReport};
+use crate::global::{get_global_state, GlobalState};
+use crate::price::Price;
+use crate::instructions::{buy_ix, collect_creator_fee_ix, create_ix, sell_ix, BuyAccounts, CreateArgs, SellAccounts};
+use crate::lookup_table::create_pumpfun_lookup_table_ixs;
 
 pub mod curve;
 pub mod constants;
+pub mod decoder;
+pub mod error;
+pub mod failure;
+pub mod events;
+pub mod global;
+pub mod idl;
+pub mod lookup_table;
 pub mod metadata;
+pub mod price;
 pub mod instructions;
-
-pub struct PumpFunClient {
-    derived_account_cache: HashMap<Pubkey, (Pubkey, Pubkey)>,
+pub mod signer;
+
+/// Client signing with any `Signer`: a `Keypair`, a `Presigner`, a remote signer or a reference to one.
+/// Trait objects such as `Arc<dyn Signer + Send + Sync>` need to be wrapped in a `signer::DynSigner`.
+pub struct PumpFunClient<S: Signer = Keypair> {
+    token_program_cache: HashMap<Pubkey, Pubkey>,
+    global_state: Option<GlobalState>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    compute_unit_margin_basis_points: Option<u64>,
     rpc_client: RpcClient,
-    wallet: Keypair,
+    wallet: S,
     wallet_pubkey: Pubkey,
 }
 
 
-impl PumpFunClient {
+impl<S: Signer> PumpFunClient<S> {
 
-    pub fn new(rpc_client: RpcClient, wallet: &Keypair) -> PumpFunClient {
+    pub fn new(rpc_client: RpcClient, wallet: S) -> PumpFunClient<S> {
 
         PumpFunClient {
-            derived_account_cache: HashMap::new(),
-            wallet: wallet.insecure_clone(),
+            token_program_cache: HashMap::new(),
+            global_state: None,
+            lookup_tables: Vec::new(),
+            compute_unit_margin_basis_points: None,
             wallet_pubkey: wallet.pubkey(),
+            wallet,
             rpc_client,
         }
     }
 
-    fn get_derived_accounts(&mut self, mint: &Pubkey) -> (Pubkey, Pubkey) {
-        if let Some(accounts) = self.derived_account_cache.get(mint) {
-            return *accounts;
+    /// Lookup tables used to compress the versioned transactions built by this client.
+    /// Once set, `buy`, `buy_exact_tokens`, `sell`, `sell_for_sol` and `create_and_buy` send v0 transactions.
+    pub fn set_lookup_tables(&mut self, lookup_tables: Vec<AddressLookupTableAccount>) {
+        self.lookup_tables = lookup_tables;
+    }
+
+    /// Simulates each transaction before signing it and sets its compute unit limit to the units
+    /// consumed plus `margin_basis_points` (1_000 adds 10%). `None` keeps the default limit and skips simulation.
+    pub fn set_compute_unit_margin(&mut self, margin_basis_points: Option<u64>) {
+        self.compute_unit_margin_basis_points = margin_basis_points;
+    }
+
+    // The owner of a mint never changes, unlike the creator of a legacy curve, so only it is cached
+    async fn get_cached_token_program(&mut self, mint: &Pubkey) -> Result<Pubkey, PumpFunError> {
+        let token_program = self.get_token_program(mint).await?;
+        self.token_program_cache.insert(*mint, token_program);
+        Ok(token_program)
+    }
+
+    /// Returns the program owning the mint, either the SPL Token or the Token-2022 program.
+    pub async fn get_token_program(&self, mint: &Pubkey) -> Result<Pubkey, PumpFunError> {
+        if let Some(token_program) = self.token_program_cache.get(mint) {
+            return Ok(*token_program);
+        }
+
+        instructions::get_token_program(&self.rpc_client, mint).await
+    }
+
+    pub async fn get_global_state(&mut self) -> Result<&GlobalState, PumpFunError> {
+        if self.global_state.is_none() {
+            self.refresh_global_state().await?;
         }
 
-        let accounts = derive_bonding_curve_accounts(mint);
-        self.derived_account_cache.insert(*mint, accounts);
-        accounts
+        Ok(self.global_state.as_ref().unwrap())
     }
 
-    pub async fn get_price(&mut self, mint: &Pubkey) -> Result<f32, Box<dyn std::error::Error>> {
-        let (bonding_curve, _) = self.get_derived_accounts(mint);
+    pub async fn refresh_global_state(&mut self) -> Result<&GlobalState, PumpFunError> {
+        let state = get_global_state(&self.rpc_client).await?;
+        Ok(self.global_state.insert(state))
+    }
+
+    pub async fn get_price(&self, mint: &Pubkey) -> Result<Price, PumpFunError> {
+        let bonding_curve = derive_bonding_curve(mint);
         let state = get_bonding_curve_state(&self.rpc_client, &bonding_curve).await?;
 
         if state.complete {
-            return Err("Curve is complete. Check Raydium reserves for price.".into());
+            return Err(PumpFunError::CurveComplete(*mint));
         }
 
         Ok(state.price())
     }
     
-    pub async fn get_balance(&self, mint: &Pubkey) -> Result<u64, Box<dyn std::error::Error>> {
+    pub async fn get_prices(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, Option<Price>>, PumpFunError> {
+        let (states, _) = get_bonding_curve_states(&self.rpc_client, mints).await?;
+
+        // Completed curves have no price on Pumpfun
+        let prices = states
+            .into_iter()
+            .map(|(mint, state)| (mint, state.filter(|state| !state.complete).map(|state| state.price())))
+            .collect();
+
+        Ok(prices)
+    }
+
+    pub async fn get_balance(&self, mint: &Pubkey) -> Result<u64, PumpFunError> {
         // Helper function to get the balance of a token account
         
-        let token_ata = get_associated_token_address_with_program_id(&self.wallet_pubkey, mint, &spl_token::id());
+        let token_program = self.get_token_program(mint).await?;
+        let token_ata = get_associated_token_address_with_program_id(&self.wallet_pubkey, mint, &token_program);
 
         match self.rpc_client.get_token_account_balance_with_commitment(&token_ata, CommitmentConfig::confirmed()).await {
             Ok(balance) => {
-                Ok(balance.value.amount.as_str().parse::<u64>().unwrap())
+                match balance.value.amount.parse::<u64>() {
+                    Ok(amount) => Ok(amount),
+                    Err(e) => Err(PumpFunError::InvalidAccount(format!("Token account {:} reported an invalid balance {:?}: {:}", token_ata, balance.value.amount, e))),
+                }
             }
             Err(e) => {
                 Err(e.into())
@@ -76,108 +148,310 @@ impl PumpFunClient {
         }
     }
 
-    pub async fn buy(&mut self, mint: &Pubkey, amount_in: u64, slippage: f32, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Signature, Box<dyn std::error::Error>> {
+    pub async fn buy(&mut self, mint: &Pubkey, amount_in: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Signature, PumpFunError> {
+        if !self.lookup_tables.is_empty() {
+            let (tx, _) = self.create_buy_versioned_transaction(mint, amount_in, slippage_basis_points, create_token_ata, priority_fee, blockhash).await?;
+            return self.send_versioned_transaction(&tx).await;
+        }
 
-        match self.create_buy_transaction(mint, amount_in, slippage, create_token_ata, priority_fee, blockhash).await {
-            Ok(tx) => {
-                match self.rpc_client.send_transaction_with_config(
-                    &tx,
-                    RpcSendTransactionConfig {
-                        skip_preflight: false,
-                        preflight_commitment: Some(CommitmentLevel::Confirmed),
-                        .. RpcSendTransactionConfig::default()
-                    }
-                ).await {
-                    Ok(signature) => {
-                        Ok(signature)
-                    },
-                    Err(e) => Err(e.into()),
-                }
-            },
-            Err(e) => Err(e),
+        let (tx, _) = self.create_buy_transaction(mint, amount_in, slippage_basis_points, create_token_ata, priority_fee, blockhash).await?;
+        self.send_transaction(&tx).await
+    }
+
+    /// Fails with `InsufficientLiquidity` if fewer than `amount_out` tokens are left on the curve.
+    pub async fn buy_exact_tokens(&mut self, mint: &Pubkey, amount_out: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Signature, PumpFunError> {
+        if !self.lookup_tables.is_empty() {
+            let (tx, _) = self.create_buy_exact_tokens_versioned_transaction(mint, amount_out, slippage_basis_points, create_token_ata, priority_fee, blockhash).await?;
+            return self.send_versioned_transaction(&tx).await;
         }
+
+        let (tx, _) = self.create_buy_exact_tokens_transaction(mint, amount_out, slippage_basis_points, create_token_ata, priority_fee, blockhash).await?;
+        self.send_transaction(&tx).await
     }
 
-    pub async fn create_buy_transaction(&mut self, mint: &Pubkey, amount_in_sol: u64, slippage: f32, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Transaction, Box<dyn std::error::Error>> {
-        let (bonding_curve, associated_bonding_curve) = self.get_derived_accounts(mint);
-        let state = get_bonding_curve_state(&self.rpc_client, &bonding_curve).await?;
+    pub async fn create_buy_transaction(&mut self, mint: &Pubkey, amount_in_sol: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(Transaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_buy(mint, amount_in_sol, slippage_basis_points, create_token_ata, priority_fee).await?;
+        Ok((self.sign_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn create_buy_versioned_transaction(&mut self, mint: &Pubkey, amount_in_sol: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(VersionedTransaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_buy(mint, amount_in_sol, slippage_basis_points, create_token_ata, priority_fee).await?;
+        Ok((self.sign_versioned_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn create_buy_exact_tokens_transaction(&mut self, mint: &Pubkey, amount_out: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(Transaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_buy_exact_tokens(mint, amount_out, slippage_basis_points, create_token_ata, priority_fee).await?;
+        Ok((self.sign_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn create_buy_exact_tokens_versioned_transaction(&mut self, mint: &Pubkey, amount_out: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(VersionedTransaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_buy_exact_tokens(mint, amount_out, slippage_basis_points, create_token_ata, priority_fee).await?;
+        Ok((self.sign_versioned_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn create_sell_transaction(&mut self, mint: &Pubkey, amount_in_token: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(Transaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_sell(mint, amount_in_token, slippage_basis_points, close_token_ata, priority_fee).await?;
+        Ok((self.sign_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn create_sell_versioned_transaction(&mut self, mint: &Pubkey, amount_in_token: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(VersionedTransaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_sell(mint, amount_in_token, slippage_basis_points, close_token_ata, priority_fee).await?;
+        Ok((self.sign_versioned_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn create_sell_for_sol_transaction(&mut self, mint: &Pubkey, amount_out_sol: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(Transaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_sell_for_sol(mint, amount_out_sol, slippage_basis_points, close_token_ata, priority_fee).await?;
+        Ok((self.sign_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn create_sell_for_sol_versioned_transaction(&mut self, mint: &Pubkey, amount_out_sol: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<(VersionedTransaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_sell_for_sol(mint, amount_out_sol, slippage_basis_points, close_token_ata, priority_fee).await?;
+        Ok((self.sign_versioned_transaction(&ixs, &[], blockhash).await?, report))
+    }
+
+    pub async fn sell(&mut self, mint: &Pubkey, amount_in: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Signature, PumpFunError> {
+        if !self.lookup_tables.is_empty() {
+            let (tx, _) = self.create_sell_versioned_transaction(mint, amount_in, slippage_basis_points, close_token_ata, priority_fee, blockhash).await?;
+            return self.send_versioned_transaction(&tx).await;
+        }
+
+        let (tx, _) = self.create_sell_transaction(mint, amount_in, slippage_basis_points, close_token_ata, priority_fee, blockhash).await?;
+        self.send_transaction(&tx).await
+    }
+
+    pub async fn sell_for_sol(&mut self, mint: &Pubkey, amount_out_sol: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Signature, PumpFunError> {
+        if !self.lookup_tables.is_empty() {
+            let (tx, _) = self.create_sell_for_sol_versioned_transaction(mint, amount_out_sol, slippage_basis_points, close_token_ata, priority_fee, blockhash).await?;
+            return self.send_versioned_transaction(&tx).await;
+        }
+
+        let (tx, _) = self.create_sell_for_sol_transaction(mint, amount_out_sol, slippage_basis_points, close_token_ata, priority_fee, blockhash).await?;
+        self.send_transaction(&tx).await
+    }
+
+    pub async fn create_token(&mut self, mint: &dyn Signer, args: &CreateArgs, priority_fee: u64, blockhash: &Hash) -> Result<Signature, PumpFunError> {
+        let tx = self.create_token_transaction(mint, args, priority_fee, blockhash).await?;
+        self.send_transaction(&tx).await
+    }
+
+    pub async fn create_token_transaction(&self, mint: &dyn Signer, args: &CreateArgs, priority_fee: u64, blockhash: &Hash) -> Result<Transaction, PumpFunError> {
+        let ixs = self.prepare_create_token(&mint.pubkey(), args, priority_fee);
+        self.sign_transaction(&ixs, &[mint], blockhash).await
+    }
+
+    pub async fn create_token_versioned_transaction(&self, mint: &dyn Signer, args: &CreateArgs, priority_fee: u64, blockhash: &Hash) -> Result<VersionedTransaction, PumpFunError> {
+        let ixs = self.prepare_create_token(&mint.pubkey(), args, priority_fee);
+        self.sign_versioned_transaction(&ixs, &[mint], blockhash).await
+    }
+
+    /// Sends a v0 transaction when lookup tables are set, the create and buy accounts can exceed the legacy size limit.
+    pub async fn create_and_buy(&mut self, mint: &dyn Signer, args: &CreateArgs, amount_in_sol: u64, slippage_basis_points: u64, priority_fee: u64, blockhash: &Hash) -> Result<Signature, PumpFunError> {
+        if !self.lookup_tables.is_empty() {
+            let (tx, _) = self.create_and_buy_versioned_transaction(mint, args, amount_in_sol, slippage_basis_points, priority_fee, blockhash).await?;
+            return self.send_versioned_transaction(&tx).await;
+        }
+
+        let (tx, _) = self.create_and_buy_transaction(mint, args, amount_in_sol, slippage_basis_points, priority_fee, blockhash).await?;
+        self.send_transaction(&tx).await
+    }
+
+    /// Creates the token and makes the dev buy in one transaction, so no other buy can land in between.
+    pub async fn create_and_buy_transaction(&mut self, mint: &dyn Signer, args: &CreateArgs, amount_in_sol: u64, slippage_basis_points: u64, priority_fee: u64, blockhash: &Hash) -> Result<(Transaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_create_and_buy(&mint.pubkey(), args, amount_in_sol, slippage_basis_points, priority_fee).await?;
+        Ok((self.sign_transaction(&ixs, &[mint], blockhash).await?, report))
+    }
+
+    pub async fn create_and_buy_versioned_transaction(&mut self, mint: &dyn Signer, args: &CreateArgs, amount_in_sol: u64, slippage_basis_points: u64, priority_fee: u64, blockhash: &Hash) -> Result<(VersionedTransaction, QuoteReport), PumpFunError> {
+        let (ixs, report) = self.prepare_create_and_buy(&mint.pubkey(), args, amount_in_sol, slippage_basis_points, priority_fee).await?;
+        Ok((self.sign_versioned_transaction(&ixs, &[mint], blockhash).await?, report))
+    }
+
+    /// Withdraws the creator fees accumulated in the wallet's creator vault.
+    pub async fn collect_creator_fee(&self, priority_fee: u64, blockhash: &Hash) -> Result<Signature, PumpFunError> {
+        let tx = self.collect_creator_fee_transaction(priority_fee, blockhash).await?;
+        self.send_transaction(&tx).await
+    }
+
+    pub async fn collect_creator_fee_transaction(&self, priority_fee: u64, blockhash: &Hash) -> Result<Transaction, PumpFunError> {
+        let ixs = self.prepare_collect_creator_fee(priority_fee);
+        self.sign_transaction(&ixs, &[], blockhash).await
+    }
+
+    pub async fn collect_creator_fee_versioned_transaction(&self, priority_fee: u64, blockhash: &Hash) -> Result<VersionedTransaction, PumpFunError> {
+        let ixs = self.prepare_collect_creator_fee(priority_fee);
+        self.sign_versioned_transaction(&ixs, &[], blockhash).await
+    }
+
+    /// Creates a lookup table owned by the wallet holding the static PumpFun accounts.
+    /// Load it with `get_lookup_table` and pass it to `set_lookup_tables` once it is active.
+    pub async fn create_lookup_table(&mut self, blockhash: &Hash) -> Result<(Signature, Pubkey), PumpFunError> {
+        let fee_recipient = self.get_global_state().await?.fee_recipient;
+        let recent_slot = self.rpc_client.get_slot_with_commitment(CommitmentConfig::finalized()).await?;
+        let (ixs, lookup_table) = create_pumpfun_lookup_table_ixs(&self.wallet_pubkey, &self.wallet_pubkey, &fee_recipient, recent_slot);
+
+        let tx = self.sign_transaction(&ixs, &[], blockhash).await?;
+        Ok((self.send_transaction(&tx).await?, lookup_table))
+    }
+
+    async fn get_tradeable_curve_state(&mut self, mint: &Pubkey) -> Result<(CurveState, BuyAccounts, FeeRates), PumpFunError> {
+        let bonding_curve = derive_bonding_curve(mint);
+        let account = get_bonding_curve_account(&self.rpc_client, &bonding_curve).await?;
+
+        let token_program = self.get_cached_token_program(mint).await?;
+        let user = self.wallet_pubkey;
+        let global = self.get_global_state().await?;
+        let fees = FeeRates::new(global, account.creator().is_some());
+        let accounts = BuyAccounts::from_state(mint, &user, global, &account, &token_program);
+        let state = account.into_state();
 
         if state.complete {
-            return Err("Curve is complete. Cannot buy on Pumpfun.".into());
+            return Err(PumpFunError::CurveComplete(*mint));
+        }
+
+        Ok((state, accounts, fees))
+    }
+
+    async fn prepare_buy(&mut self, mint: &Pubkey, amount_in_sol: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64) -> Result<(Vec<Instruction>, QuoteReport), PumpFunError> {
+        let (state, accounts, fees) = self.get_tradeable_curve_state(mint).await?;
+
+        let quote = state.quote_buy_with_fee(amount_in_sol, fees);
+
+        let max_amount_in_sol = sol_limit_with_slippage(quote.net_sol, slippage_basis_points, true);
+
+        let ixs = self.buy_order_instructions(&accounts, quote.token_amount, max_amount_in_sol, create_token_ata, priority_fee);
+        Ok((ixs, QuoteReport::buy(&state, quote)))
+    }
+
+    async fn prepare_buy_exact_tokens(&mut self, mint: &Pubkey, amount_out: u64, slippage_basis_points: u64, create_token_ata: bool, priority_fee: u64) -> Result<(Vec<Instruction>, QuoteReport), PumpFunError> {
+        let (state, accounts, fees) = self.get_tradeable_curve_state(mint).await?;
+
+        // The curve quote is clamped to the tokens left, an exact order must fill completely
+        let quote = match state.quote_buy_exact_out(amount_out, fees) {
+            Some(quote) if quote.token_amount == amount_out => quote,
+            _ => return Err(PumpFunError::InsufficientLiquidity),
+        };
+
+        let max_amount_in_sol = sol_limit_with_slippage(quote.net_sol, slippage_basis_points, true);
+
+        let ixs = self.buy_order_instructions(&accounts, quote.token_amount, max_amount_in_sol, create_token_ata, priority_fee);
+        Ok((ixs, QuoteReport::buy(&state, quote)))
+    }
+
+    async fn prepare_sell(&mut self, mint: &Pubkey, amount_in_token: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64) -> Result<(Vec<Instruction>, QuoteReport), PumpFunError> {
+        let (state, accounts, fees) = self.get_tradeable_curve_state(mint).await?;
+
+        let quote = state.quote_sell_with_fee(amount_in_token, fees);
+
+        let min_amount_out_sol = sol_limit_with_slippage(quote.net_sol, slippage_basis_points, false);
+
+        let ixs = self.sell_order_instructions(&accounts.into(), amount_in_token, min_amount_out_sol, close_token_ata, priority_fee)?;
+        Ok((ixs, QuoteReport::sell(&state, quote)))
+    }
+
+    async fn prepare_sell_for_sol(&mut self, mint: &Pubkey, amount_out_sol: u64, slippage_basis_points: u64, close_token_ata: bool, priority_fee: u64) -> Result<(Vec<Instruction>, QuoteReport), PumpFunError> {
+        let (state, accounts, fees) = self.get_tradeable_curve_state(mint).await?;
+
+        let quote = match state.quote_sell_exact_out(amount_out_sol, fees) {
+            Some(quote) => quote,
+            None => return Err(PumpFunError::InsufficientLiquidity),
+        };
+
+        let min_amount_out_sol = sol_limit_with_slippage(quote.net_sol, slippage_basis_points, false);
+
+        let ixs = self.sell_order_instructions(&accounts.into(), quote.token_amount, min_amount_out_sol, close_token_ata, priority_fee)?;
+        Ok((ixs, QuoteReport::sell(&state, quote)))
+    }
+
+    fn prepare_create_token(&self, mint: &Pubkey, args: &CreateArgs, priority_fee: u64) -> Vec<Instruction> {
+        let mut ixs: Vec<Instruction> = Vec::new();
+
+        if priority_fee > 0 {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
         }
 
-        let price = state.price();
+        ixs.push(create_ix(
+            mint,
+            &self.wallet_pubkey,
+            args));
 
-        let amount_out = (amount_in_sol as f32 / price / 1_000.0) as u64;
-        let max_amount_in_sol = (amount_in_sol as f32 * (1.0 + slippage)) as u64;
+        ixs
+    }
 
+    fn prepare_collect_creator_fee(&self, priority_fee: u64) -> Vec<Instruction> {
         let mut ixs: Vec<Instruction> = Vec::new();
 
         if priority_fee > 0 {
             ixs.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
         }
 
-        let token_ata = if create_token_ata {
-            let create_ata_ix = instruction::create_associated_token_account(
-                &self.wallet_pubkey,
-                &self.wallet_pubkey,
-                mint,
-                &constants::TOKEN_PROGRAM_ID,
-            );
+        ixs.push(collect_creator_fee_ix(&self.wallet_pubkey));
 
-            ixs.push(create_ata_ix.clone());
-            create_ata_ix.accounts[1].pubkey
-        } else {
-            get_associated_token_address_with_program_id(&self.wallet_pubkey, mint, &spl_token::id())
-        };
+        ixs
+    }
 
-        ixs.push(buy_amount_out_ix(
-            mint,
-            &bonding_curve,
-            &associated_bonding_curve,
-            &self.wallet_pubkey,
-            &token_ata,
-            amount_out,
-            max_amount_in_sol));
+    async fn prepare_create_and_buy(&mut self, mint: &Pubkey, args: &CreateArgs, amount_in_sol: u64, slippage_basis_points: u64, priority_fee: u64) -> Result<(Vec<Instruction>, QuoteReport), PumpFunError> {
+        let user = self.wallet_pubkey;
+        let global = self.get_global_state().await?;
+        // The curve the create instruction is about to initialize
+        let account = BondingCurveAccount::V2 { state: CurveState::from_global(global), creator: args.creator };
+        let fees = FeeRates::new(global, account.creator().is_some());
+
+        // Tokens launched through `create` are always SPL Token mints
+        let accounts = BuyAccounts::from_state(mint, &user, global, &account, &constants::TOKEN_PROGRAM_ID);
+        let state = account.into_state();
+
+        let quote = state.quote_buy_with_fee(amount_in_sol, fees);
+        let max_amount_in_sol = sol_limit_with_slippage(quote.net_sol, slippage_basis_points, true);
+
+        let mut ixs = self.prepare_create_token(mint, args, priority_fee);
+        ixs.extend(self.buy_instructions(&accounts, quote.token_amount, max_amount_in_sol, true));
 
-        Ok(Transaction::new_signed_with_payer(&ixs.to_vec(), Some(&self.wallet.pubkey()), &[&self.wallet], *blockhash))
+        Ok((ixs, QuoteReport::buy(&state, quote)))
     }
-    
-    pub async fn create_sell_transaction(&mut self, mint: &Pubkey, amount_in_token: u64, slippage: f32, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Transaction, Box<dyn std::error::Error>> {
-        let (bonding_curve, associated_bonding_curve) = self.get_derived_accounts(mint);
-        let state = get_bonding_curve_state(&self.rpc_client, &bonding_curve).await?;
 
-        if state.complete {
-            return Err("Curve is complete. Cannot sell on Pumpfun.".into());
+    fn buy_order_instructions(&self, accounts: &BuyAccounts, amount_out: u64, max_amount_in_sol: u64, create_token_ata: bool, priority_fee: u64) -> Vec<Instruction> {
+        let mut ixs: Vec<Instruction> = Vec::new();
+
+        if priority_fee > 0 {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
         }
 
-        let price = state.price();
-        
-        let amount_out_sol = (amount_in_token as f32 * price * 1000.0) as u64;
-        let min_amount_out_sol = amount_out_sol - (amount_out_sol as f32 * slippage) as u64;
-        
+        ixs.extend(self.buy_instructions(accounts, amount_out, max_amount_in_sol, create_token_ata));
+        ixs
+    }
+
+    fn buy_instructions(&self, accounts: &BuyAccounts, amount_out: u64, max_amount_in_sol: u64, create_token_ata: bool) -> Vec<Instruction> {
+        let mut ixs: Vec<Instruction> = Vec::new();
+
+        if create_token_ata {
+            ixs.push(instruction::create_associated_token_account(
+                &self.wallet_pubkey,
+                &self.wallet_pubkey,
+                &accounts.mint,
+                &accounts.token_program,
+            ));
+        }
+
+        ixs.push(buy_ix(accounts, amount_out, max_amount_in_sol));
+
+        ixs
+    }
+
+    fn sell_order_instructions(&self, accounts: &SellAccounts, amount_in_token: u64, min_amount_out_sol: u64, close_token_ata: bool, priority_fee: u64) -> Result<Vec<Instruction>, PumpFunError> {
         let mut ixs: Vec<Instruction> = Vec::new();
 
         if priority_fee > 0 {
             ixs.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
         }
-        
-        let token_ata = get_associated_token_address_with_program_id(&self.wallet_pubkey, mint, &spl_token::id());
 
-        ixs.push(sell_amount_in_ix(
-            mint,
-            &bonding_curve,
-            &associated_bonding_curve,
-            &self.wallet_pubkey,
-            &token_ata,
-            amount_in_token,
-            min_amount_out_sol));
+        ixs.push(sell_ix(accounts, amount_in_token, min_amount_out_sol));
 
         if close_token_ata {
+            // The Token-2022 builder accepts either token program
             ixs.push(
                 close_account(
-                    &constants::TOKEN_PROGRAM_ID,
-                    &token_ata,
+                    &accounts.token_program,
+                    &accounts.associated_user,
                     &self.wallet_pubkey,
                     &self.wallet_pubkey,
                     &[], // no rent account
@@ -185,28 +459,107 @@ impl PumpFunClient {
             );
         };
 
-        Ok(Transaction::new_signed_with_payer(&ixs.to_vec(), Some(&self.wallet.pubkey()), &[&self.wallet], *blockhash))
-    }
-
-    pub async fn sell(&mut self, mint: &Pubkey, amount_in: u64, slippage: f32, close_token_ata: bool, priority_fee: u64, blockhash: &Hash) -> Result<Signature, Box<dyn std::error::Error>> {
-
-        match self.create_sell_transaction(mint, amount_in, slippage, close_token_ata, priority_fee, blockhash).await {
-            Ok(tx) => {
-                match self.rpc_client.send_transaction_with_config(
-                    &tx,
-                    RpcSendTransactionConfig {
-                        skip_preflight: false,
-                        preflight_commitment: Some(CommitmentLevel::Confirmed),
-                        .. RpcSendTransactionConfig::default()
-                    }
-                ).await {
-                    Ok(signature) => {
-                        Ok(signature)
-                    },
-                    Err(e) => Err(e.into()),
+        Ok(ixs)
+    }
+
+    async fn sign_transaction(&self, ixs: &[Instruction], signers: &[&dyn Signer], blockhash: &Hash) -> Result<Transaction, PumpFunError> {
+        let ixs = self.with_compute_unit_limit(ixs, blockhash).await?;
+
+        let mut all_signers: Vec<&dyn Signer> = vec![&self.wallet];
+        all_signers.extend_from_slice(signers);
+
+        // Remote signers can fail, so sign fallibly instead of panicking
+        let mut tx = Transaction::new_with_payer(&ixs, Some(&self.wallet_pubkey));
+        tx.try_sign(&all_signers, *blockhash)?;
+
+        Ok(tx)
+    }
+
+    async fn sign_versioned_transaction(&self, ixs: &[Instruction], signers: &[&dyn Signer], blockhash: &Hash) -> Result<VersionedTransaction, PumpFunError> {
+        let ixs = self.with_compute_unit_limit(ixs, blockhash).await?;
+
+        let mut all_signers: Vec<&dyn Signer> = vec![&self.wallet];
+        all_signers.extend_from_slice(signers);
+
+        let message = v0::Message::try_compile(&self.wallet_pubkey, &ixs, &self.lookup_tables, *blockhash)?;
+        Ok(VersionedTransaction::try_new(VersionedMessage::V0(message), &all_signers)?)
+    }
+
+    async fn with_compute_unit_limit(&self, ixs: &[Instruction], blockhash: &Hash) -> Result<Vec<Instruction>, PumpFunError> {
+        let mut ixs = ixs.to_vec();
+
+        let margin_basis_points = match self.compute_unit_margin_basis_points {
+            Some(margin_basis_points) => margin_basis_points,
+            None => return Ok(ixs),
+        };
+
+        // Simulate under the maximum limit so the measurement is not capped by the default one
+        ixs.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(constants::MAX_COMPUTE_UNIT_LIMIT));
+
+        let message = v0::Message::try_compile(&self.wallet_pubkey, &ixs, &self.lookup_tables, *blockhash)?;
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+            message: VersionedMessage::V0(message),
+        };
+
+        let simulation = self.rpc_client.simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(CommitmentConfig::confirmed()),
+                .. RpcSimulateTransactionConfig::default()
+            }
+        ).await?.value;
+
+        if let Some(err) = simulation.err {
+            let program_ids: Vec<Pubkey> = tx.message.instructions().iter().map(|ix| tx.message.static_account_keys()[ix.program_id_index as usize]).collect();
+            let logs = simulation.logs.unwrap_or_default();
+
+            return Err(decode_transaction_error(&err, &program_ids, &logs).into());
+        }
+
+        let units_consumed = match simulation.units_consumed {
+            Some(units_consumed) => units_consumed,
+            None => return Err(PumpFunError::Transaction("Simulation did not report consumed compute units.".to_string())),
+        };
+
+        let limit = units_consumed as u128 * (10_000 + margin_basis_points as u128) / 10_000;
+        ixs[0] = ComputeBudgetInstruction::set_compute_unit_limit(limit.min(constants::MAX_COMPUTE_UNIT_LIMIT as u128) as u32);
+
+        Ok(ixs)
+    }
+
+    async fn send_transaction(&self, tx: &Transaction) -> Result<Signature, PumpFunError> {
+        let program_ids: Vec<Pubkey> = tx.message.program_ids().into_iter().copied().collect();
+        self.send_serializable_transaction(tx, &program_ids).await
+    }
+
+    async fn send_versioned_transaction(&self, tx: &VersionedTransaction) -> Result<Signature, PumpFunError> {
+        // Program ids are never loaded from lookup tables, the static keys are enough
+        let program_ids: Vec<Pubkey> = tx.message.instructions().iter().map(|ix| tx.message.static_account_keys()[ix.program_id_index as usize]).collect();
+        self.send_serializable_transaction(tx, &program_ids).await
+    }
+
+    async fn send_serializable_transaction(&self, tx: &impl SerializableTransaction, program_ids: &[Pubkey]) -> Result<Signature, PumpFunError> {
+        let result = self.rpc_client.send_transaction_with_config(
+            tx,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                .. RpcSendTransactionConfig::default()
+            }
+        ).await;
+
+        match result {
+            Ok(signature) => Ok(signature),
+            Err(e) => {
+                // Surface the program error a failed preflight ran into rather than the raw RPC error
+                match decode_client_error(&e, program_ids) {
+                    Some(failure) => Err(failure.into()),
+                    None => Err(e.into()),
                 }
-            },
-            Err(e) => Err(e),
+            }
         }
-    }    
+    }
 }
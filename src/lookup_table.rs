@@ -0,0 +1,53 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::address_lookup_table::instruction::{create_lookup_table, extend_lookup_table};
+use solana_program::address_lookup_table::state::AddressLookupTable;
+use solana_program::address_lookup_table::AddressLookupTableAccount;
+use solana_program::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::{ASSOC_TOKEN_ACC_PROGRAM_ID, EVENT_AUTHORITY, MPL_TOKEN_METADATA_PROGRAM_ID, PUMPFUN_GLOBAL, PUMPFUN_PROGRAM_ID, SYSTEM_PROGRAM_ID, SYSTEM_RENT_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crate::error::PumpFunError;
+use crate::instructions::derive_mint_authority;
+
+// Accounts shared by every PumpFun create, buy and sell. `fee_recipient` is read from the Global account,
+// see `GlobalState::fee_recipient`
+pub fn pumpfun_static_accounts(fee_recipient: &Pubkey) -> Vec<Pubkey> {
+    vec![
+        PUMPFUN_PROGRAM_ID,
+        PUMPFUN_GLOBAL,
+        *fee_recipient,
+        SYSTEM_PROGRAM_ID,
+        SYSTEM_RENT_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOC_TOKEN_ACC_PROGRAM_ID,
+        EVENT_AUTHORITY,
+        MPL_TOKEN_METADATA_PROGRAM_ID,
+        derive_mint_authority(),
+    ]
+}
+
+/// Instructions creating a lookup table holding the static PumpFun accounts, and its address.
+/// `recent_slot` must be a recent finalized slot.
+pub fn create_pumpfun_lookup_table_ixs(authority: &Pubkey, payer: &Pubkey, fee_recipient: &Pubkey, recent_slot: u64) -> (Vec<Instruction>, Pubkey) {
+
+    let (create_ix, lookup_table) = create_lookup_table(*authority, *payer, recent_slot);
+    let extend_ix = extend_lookup_table(lookup_table, *authority, Some(*payer), pumpfun_static_accounts(fee_recipient));
+
+    (vec![create_ix, extend_ix], lookup_table)
+}
+
+pub async fn get_lookup_table(client: &RpcClient, lookup_table: &Pubkey) -> Result<AddressLookupTableAccount, PumpFunError> {
+
+    let account_data = client.get_account_data(lookup_table).await?;
+
+    let table = match AddressLookupTable::deserialize(&account_data) {
+        Ok(table) => table,
+        Err(e) => return Err(PumpFunError::InvalidAccount(format!("Lookup table {:} could not be read: {:}", lookup_table, e))),
+    };
+
+    Ok(AddressLookupTableAccount {
+        key: *lookup_table,
+        addresses: table.addresses.to_vec(),
+    })
+}
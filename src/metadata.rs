@@ -4,7 +4,7 @@ use solana_sdk::pubkey::Pubkey;
 use serde::{Deserialize, Serialize};
 
 use log::error;
-use std::error::Error;
+use crate::error::PumpFunError;
 use std::str::FromStr;
 
 pub fn deserialize_pubkey<'de, D>(deserializer: D) -> Result<Pubkey, D::Error>
@@ -74,7 +74,7 @@ pub struct TokenMetaData {
 }
 
 
-pub async fn get_token_metadata(mint: &Pubkey) -> Result<TokenMetaData, Box<dyn Error>> {
+pub async fn get_token_metadata(mint: &Pubkey) -> Result<TokenMetaData, PumpFunError> {
     match reqwest::get(format!("https://frontend-api.pump.fun/coins/{:}", mint)).await {
         Ok(response) => {
             match response.status() {
@@ -85,10 +85,10 @@ pub async fn get_token_metadata(mint: &Pubkey) -> Result<TokenMetaData, Box<dyn
                 }
                 _ => {
                     error!("Error retrieving PumpFun metadata: {:?}", response.status());
-                    Err(format!("Error: {:?}", response.status()).into())
+                    Err(PumpFunError::HttpStatus(response.status()))
                 }
             }
         }
-        Err(e) => Err(Box::new(e)),
+        Err(e) => Err(e.into()),
     }
 }
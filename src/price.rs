@@ -0,0 +1,156 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+use crate::constants::TOKEN_DECIMALS;
+
+// Exact ratio of lamports to token base units, only rounded when converted for display
+#[derive(Debug, Clone, Copy)]
+pub struct Price {
+    lamports: u64,
+    base_units: u64,
+}
+
+impl Price {
+    pub fn new(lamports: u64, base_units: u64) -> Price {
+        Price { lamports, base_units }
+    }
+
+    pub fn zero() -> Price {
+        Price { lamports: 0, base_units: 1 }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.lamports == 0 || self.base_units == 0
+    }
+
+    pub fn lamports(&self) -> u64 {
+        self.lamports
+    }
+
+    pub fn base_units(&self) -> u64 {
+        self.base_units
+    }
+
+    pub fn lamports_per_base_unit(&self) -> f64 {
+
+        if self.is_zero() {
+            return 0.0;
+        }
+
+        self.lamports as f64 / self.base_units as f64
+    }
+
+    pub fn sol_per_token(&self) -> f64 {
+        self.lamports_per_base_unit() * 10f64.powi(TOKEN_DECIMALS as i32) / LAMPORTS_PER_SOL as f64
+    }
+
+    pub fn usd_per_token(&self, sol_price_usd: f64) -> f64 {
+        self.sol_per_token() * sol_price_usd
+    }
+
+    /// Lamports value of `base_units` tokens at this price, rounded down.
+    pub fn value_of(&self, base_units: u64) -> u64 {
+
+        if self.is_zero() {
+            return 0;
+        }
+
+        (base_units as u128 * self.lamports as u128 / self.base_units as u128) as u64
+    }
+
+    /// Absolute percentage change from this price to `other`.
+    pub fn change_percent(&self, other: &Price) -> f64 {
+
+        if self.is_zero() {
+            return 0.0;
+        }
+
+        let from = self.lamports as u128 * other.base_units as u128;
+        let to = other.lamports as u128 * self.base_units as u128;
+
+        from.abs_diff(to) as f64 / from as f64 * 100.0
+    }
+}
+
+impl PartialEq for Price {
+    fn eq(&self, other: &Price) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Price) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Price {
+    fn cmp(&self, other: &Price) -> Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => (self.lamports as u128 * other.base_units as u128).cmp(&(other.lamports as u128 * self.base_units as u128)),
+        }
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.12} SOL", self.sol_per_token())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_across_denominators() {
+        assert_eq!(Price::new(1, 2), Price::new(500, 1_000));
+        assert!(Price::new(1, 3) < Price::new(1, 2));
+        assert!(Price::new(2, 3) > Price::new(1, 2));
+        assert_eq!(Price::new(1, 2).max(Price::new(3, 4)), Price::new(3, 4));
+
+        // Every zero price is equal and below any other price
+        assert_eq!(Price::zero(), Price::new(0, 1_000));
+        assert_eq!(Price::zero(), Price::new(5, 0));
+        assert!(Price::zero() < Price::new(1, u64::MAX));
+    }
+
+    #[test]
+    fn convert_to_token_prices() {
+        // Spot price of a fresh curve
+        let price = Price::new(30_000_000_000, 1_073_000_000_000_000);
+
+        assert!((price.sol_per_token() - 0.000_000_027_959).abs() < 1e-12);
+        assert!((price.usd_per_token(200.0) - 0.000_005_591_8).abs() < 1e-10);
+
+        assert_eq!(Price::new(1, 1).sol_per_token(), 0.001);
+        assert_eq!(Price::zero().sol_per_token(), 0.0);
+    }
+
+    #[test]
+    fn value_of_tokens() {
+        let price = Price::new(30_000_000_000, 1_073_000_000_000_000);
+
+        assert_eq!(price.value_of(1_073_000_000_000_000), 30_000_000_000);
+        // Rounded down
+        assert_eq!(price.value_of(34_612_903_225_806), 967_741_935);
+        assert_eq!(price.value_of(u64::MAX), (u64::MAX as u128 * 30_000_000_000 / 1_073_000_000_000_000) as u64);
+        assert_eq!(Price::zero().value_of(1_000), 0);
+    }
+
+    #[test]
+    fn change_in_percent() {
+        assert_eq!(Price::new(100, 1).change_percent(&Price::new(110, 1)), 10.0);
+        assert_eq!(Price::new(100, 1).change_percent(&Price::new(90, 1)), 10.0);
+        assert_eq!(Price::new(1, 2).change_percent(&Price::new(3, 4)), 50.0);
+        assert_eq!(Price::new(1, 2).change_percent(&Price::new(2, 4)), 0.0);
+        assert_eq!(Price::zero().change_percent(&Price::new(1, 1)), 0.0);
+    }
+}
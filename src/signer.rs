@@ -0,0 +1,24 @@
+use std::ops::Deref;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::{Signer, SignerError};
+
+/// Wraps a pointer to an unsized signer, e.g. `Arc<dyn Signer + Send + Sync>` or `Box<dyn Signer>`.
+/// Solana only implements `Signer` for pointers to sized signers, so these can't be passed to `PumpFunClient` directly.
+/// The wrapper is `Send` and `Sync` when the pointer is.
+#[derive(Debug, Clone)]
+pub struct DynSigner<P>(pub P);
+
+impl<P> Signer for DynSigner<P> where P: Deref, P::Target: Signer {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        self.0.try_pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        self.0.try_sign_message(message)
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.0.is_interactive()
+    }
+}